@@ -0,0 +1,14 @@
+use crate::{get_lines::get_lines, map::Map, render::{render, RenderStyle}};
+
+pub fn draw_main(args: &Vec<&String>) {
+    if args.len() < 1 {
+        panic!("Must provide the path of the file to open");
+    }
+    let path = args[0];
+    let style = args.get(1)
+        .map(|s| RenderStyle::parse(s).unwrap_or_else(|| panic!("Unknown style {s}, use plain or ansi")))
+        .unwrap_or(RenderStyle::Plain);
+    let map = Map::from_lines(get_lines(path)).unwrap_or_else(|err| panic!("{err}"));
+
+    print!("{}", render(&map, &map.to_loop_map(), style));
+}
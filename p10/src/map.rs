@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 use crate::pipe::{Tile, Direction, LoopTile};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -10,20 +12,47 @@ impl Coords {
     pub fn new(x: usize, y: usize) -> Self { Self { x, y }}
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartError {
+    NoStart,
+    NoLoop,
+    AmbiguousLoop(Vec<[Direction; 2]>)
+}
+
+impl Display for StartError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StartError::NoStart => write!(f, "The map has no start tile"),
+            StartError::NoLoop => write!(f, "No closed loop passes through the start tile"),
+            StartError::AmbiguousLoop(candidates) => {
+                write!(f, "Several closed loops pass through the start tile, leaving it through")?;
+                for [d1, d2] in candidates.iter() {
+                    write!(f, " {d1:?}/{d2:?}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
 pub struct Map {
     data: Vec<Vec<Tile>>,
     start: Coords
 }
 
 impl Map {
-    pub fn from_lines(lines: impl Iterator<Item = String>) -> Self {
+    // Parses the map and replaces the start with the pipe that closes its loop
+    pub fn from_lines(lines: impl Iterator<Item = String>) -> Result<Self, StartError> {
         let data: Vec<Vec<_>> = lines.map(|line|
             line.chars().map(Tile::from_char).collect())
             .collect();
         let start = data.iter().enumerate().filter_map(|(y, row)|
             row.iter().enumerate().find(|(_, el)| **el == Tile::Start).map(|(x, _)| Coords::new(x, y)))
-            .nth(0).unwrap();
-        Self { data, start }
+            .nth(0).ok_or(StartError::NoStart)?;
+        let mut map = Self { data, start };
+        let [d1, d2] = map.infer_start()?;
+        map.data[start.y][start.x] = Tile::Pipe(d1, d2);
+        Ok(map)
     }
 
     pub fn height(&self) -> usize { self.data.len() }
@@ -40,14 +69,49 @@ impl Map {
 
     pub fn index(&self, coords: &Coords) -> &Tile { &self.data[coords.y][coords.x] }
 
+    pub fn start(&self) -> Coords { self.start }
+
+    // Follows the pipes leaving the start through `dir`, returning the direction
+    // the start is re-entered from if they lead back to it
+    fn trace_from_start(&self, dir: Direction) -> Option<Direction> {
+        let mut at = self.start;
+        let mut to_next = dir;
+        // A path through the pipes never visits a tile twice, so it is no longer than the map
+        for _ in 0..self.width() * self.height() {
+            let next = self.move_coords(at, to_next)?;
+            if !self.index(&at).connect(self.index(&next), to_next) {
+                return None;
+            }
+            if next == self.start {
+                return Some(to_next.reverse());
+            }
+            to_next = self.index(&next).follow(to_next.reverse());
+            at = next;
+        }
+        None
+    }
+
+    fn infer_start(&self) -> Result<[Direction; 2], StartError> {
+        let mut candidates: Vec<[Direction; 2]> = Vec::new();
+        for dir in Direction::all() {
+            if let Some(back) = self.trace_from_start(dir) {
+                if !candidates.iter().any(|c| c.contains(&dir)) {
+                    candidates.push([dir, back]);
+                }
+            }
+        }
+        match candidates.len() {
+            0 => Err(StartError::NoLoop),
+            1 => Ok(candidates[0]),
+            _ => Err(StartError::AmbiguousLoop(candidates))
+        }
+    }
+
     pub fn start_directions(&self) -> [Direction; 2] {
-        let start = self.index(&self.start);
-        let mut neighbors: Vec<_> = Direction::all().iter()
-            .filter_map(|dir| self.move_coords(self.start, *dir)
-                .and_then(|to_coords| if start.connect(self.index(&to_coords), *dir) { Some(*dir) } else { None })
-            ).collect();
-        if neighbors.len() != 2 { panic!("Did not find two neighbors of start"); };
-        [neighbors[0], neighbors[1]]
+        match self.index(&self.start) {
+            Tile::Pipe(d1, d2) => [*d1, *d2],
+            _ => panic!("The start has not been inferred")
+        }
     }
 
     pub fn half_distance(&self) -> usize {
@@ -87,34 +151,58 @@ impl Map {
     }
 }
 
-fn count_row(row: &Vec<LoopTile>) -> usize {
-    row.iter().fold((false, 0usize, None), |(inside, count, normal), tile| match tile {
+// Scans the row keeping track of whether we are inside the loop, and which way the
+// pipe we are running along came from so that we know if it crosses the row
+fn inside_row(row: &[LoopTile]) -> Vec<bool> {
+    let mut flags = Vec::with_capacity(row.len());
+    row.iter().fold((false, None), |(inside, normal), tile| match tile {
         LoopTile::Pipe(d1, d2) => {
+            flags.push(false);
             match normal {
                 Some(normal_dir) => if *d1 == Direction::East || *d2 == Direction::East {
-                    (inside, count, normal)
+                    (inside, normal)
+                }
+                else if *d1 == normal_dir || *d2 == normal_dir {
+                    (inside, None)
                 }
                 else {
-                    if *d1 == normal_dir || *d2 == normal_dir {
-                       (inside, count, None)
-                    }
-                    else {
-                        (!inside, count, None)
-                    }
+                    (!inside, None)
                 },
                 None => if *d1 == Direction::East {
-                    (inside, count, Some(*d2))
+                    (inside, Some(*d2))
                 }
                 else if *d2 == Direction::East {
-                    (inside, count, Some(*d1))
+                    (inside, Some(*d1))
                 }
                 else {
-                    (!inside, count, None)
+                    (!inside, None)
                 },
             }
         },
-        LoopTile::NotPipe => (inside, count + if inside { 1 } else { 0 }, None),
-    }).1
+        LoopTile::NotPipe => {
+            flags.push(inside);
+            (inside, None)
+        },
+    });
+    flags
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AreaStrategy {
+    Scanline,
+    Pick,
+    FloodFill
+}
+
+impl AreaStrategy {
+    pub fn parse(s: &str) -> Option<AreaStrategy> {
+        match s {
+            "scanline" => Some(AreaStrategy::Scanline),
+            "pick" => Some(AreaStrategy::Pick),
+            "flood" => Some(AreaStrategy::FloodFill),
+            _ => None
+        }
+    }
 }
 
 pub struct LoopMap {
@@ -123,7 +211,177 @@ pub struct LoopMap {
 }
 
 impl LoopMap {
-    pub fn count_inside(&self) -> usize {
-        self.data.iter().map(count_row).sum()
+    pub fn count_inside(&self, strategy: AreaStrategy) -> usize {
+        match strategy {
+            AreaStrategy::Scanline => self.inside_tiles().iter().flatten().filter(|inside| **inside).count(),
+            AreaStrategy::Pick => self.count_inside_pick(),
+            AreaStrategy::FloodFill => self.count_inside_flood(),
+        }
+    }
+
+    // Which tiles are enclosed by the loop, by scanline
+    pub fn inside_tiles(&self) -> Vec<Vec<bool>> {
+        self.data.iter().map(|row| inside_row(row)).collect()
+    }
+
+    pub fn is_loop(&self, coords: &Coords) -> bool {
+        matches!(self.data[coords.y][coords.x], LoopTile::Pipe(_, _))
     }
-}
\ No newline at end of file
+
+    fn height(&self) -> usize { self.data.len() }
+    fn width(&self) -> usize { if self.height() > 0 { self.data[0].len() } else { 0 }}
+
+    // The loop in order, starting from the start tile
+    fn loop_coords(&self) -> Vec<Coords> {
+        let mut coords = vec![self.start];
+        let mut to_next = match self.data[self.start.y][self.start.x] {
+            LoopTile::Pipe(d1, _) => d1,
+            LoopTile::NotPipe => panic!("The start is not on the loop"),
+        };
+        loop {
+            let at = *coords.last().unwrap();
+            // The loop never leaves the map, so these cannot wrap
+            let next = match to_next {
+                Direction::North => Coords::new(at.x, at.y - 1),
+                Direction::East => Coords::new(at.x + 1, at.y),
+                Direction::South => Coords::new(at.x, at.y + 1),
+                Direction::West => Coords::new(at.x - 1, at.y),
+            };
+            if next == self.start {
+                break coords;
+            }
+            to_next = match self.data[next.y][next.x] {
+                LoopTile::Pipe(d1, d2) => if d1 == to_next.reverse() { d2 } else { d1 },
+                LoopTile::NotPipe => panic!("The loop is broken"),
+            };
+            coords.push(next);
+        }
+    }
+
+    // Shoelace formula for the area enclosed by the tile centres, then
+    // Pick's theorem: A = i + b/2 - 1 => i = A + 1 - b/2
+    fn count_inside_pick(&self) -> usize {
+        let coords = self.loop_coords();
+        let signed_area2: i64 = coords.iter().zip(coords.iter().cycle().skip(1))
+            .map(|(a, b)| a.x as i64 * b.y as i64 - a.y as i64 * b.x as i64)
+            .sum();
+        let area = (signed_area2 / 2).unsigned_abs() as usize;
+        area + 1 - coords.len() / 2
+    }
+
+    // Every tile becomes 3x3 cells with the pipe drawn through the middle, so that the
+    // outside can flood through gaps between adjacent pipes. A one cell border lets it go all the way around.
+    fn count_inside_flood(&self) -> usize {
+        let width = self.width() * 3 + 2;
+        let height = self.height() * 3 + 2;
+        let mut blocked = vec![vec![false; width]; height];
+        for (y, row) in self.data.iter().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
+                if let LoopTile::Pipe(d1, d2) = tile {
+                    let (cx, cy) = (x * 3 + 2, y * 3 + 2);
+                    blocked[cy][cx] = true;
+                    for dir in [d1, d2] {
+                        match dir {
+                            Direction::North => blocked[cy - 1][cx] = true,
+                            Direction::East => blocked[cy][cx + 1] = true,
+                            Direction::South => blocked[cy + 1][cx] = true,
+                            Direction::West => blocked[cy][cx - 1] = true,
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut outside = vec![vec![false; width]; height];
+        let mut to_explore = vec![(0usize, 0usize)];
+        outside[0][0] = true;
+        while let Some((x, y)) = to_explore.pop() {
+            let neighbors = [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)];
+            for (nx, ny) in neighbors {
+                if nx < width && ny < height && !blocked[ny][nx] && !outside[ny][nx] {
+                    outside[ny][nx] = true;
+                    to_explore.push((nx, ny));
+                }
+            }
+        }
+
+        self.data.iter().enumerate()
+            .map(|(y, row)| row.iter().enumerate()
+                .filter(|(x, tile)| matches!(tile, LoopTile::NotPipe) && !outside[y * 3 + 2][x * 3 + 2])
+                .count())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::pipe::Direction;
+    use super::{Map, StartError, AreaStrategy};
+
+    #[test]
+    fn vscode_help() { }
+
+    fn map_str(s: &str) -> Result<Map, StartError> {
+        Map::from_lines(s.lines().map(|l| l.to_string()))
+    }
+
+    #[test_case("-L|F7
+7S-7|
+L|7||
+-L-J|
+L|-JF", 4; "example 1")]
+    #[test_case("7-F7-
+.FJ|7
+SJLL7
+|F--J
+LJ.LJ", 8; "example 2")]
+    #[test_case("F7.
+S|.
+LJ.", 3; "start on the side")]
+    #[test_case("-F7.
+-SJ-
+.|..", 2; "junk touching start")]
+    fn test_half_distance(s: &str, result: usize) {
+        assert_eq!(result, map_str(s).unwrap().half_distance());
+    }
+
+    #[test_case("F7.
+|S-
+LJ.", [Direction::North, Direction::South]; "dangling east")]
+    fn test_start_directions(s: &str, result: [Direction; 2]) {
+        assert_eq!(result, map_str(s).unwrap().start_directions());
+    }
+
+    #[test_case("...
+.S.
+...", StartError::NoLoop; "no loop")]
+    #[test_case("...
+...", StartError::NoStart; "no start")]
+    #[test_case("F7..
+LS-7
+.L-J", StartError::AmbiguousLoop(vec![[Direction::North, Direction::West], [Direction::East, Direction::South]]); "ambiguous")]
+    fn test_start_error(s: &str, error: StartError) {
+        assert_eq!(Some(error), map_str(s).err());
+    }
+
+    #[test_case(include_str!("test_input_1.txt"), 1; "input 1")]
+    #[test_case(include_str!("test_input_2.txt"), 1; "input 2")]
+    #[test_case(include_str!("test_input_3.txt"), 10; "input 3")]
+    #[test_case("..........
+.S------7.
+.|F----7|.
+.||....||.
+.||....||.
+.|L-7F-J|.
+.|..||..|.
+.L--JL--J.
+..........", 4; "squeeze")]
+    fn test_count_inside(s: &str, result: usize) {
+        let loop_map = map_str(s).unwrap().to_loop_map();
+        for strategy in [AreaStrategy::Scanline, AreaStrategy::Pick, AreaStrategy::FloodFill] {
+            assert_eq!(result, loop_map.count_inside(strategy), "{strategy:?}");
+        }
+    }
+}
@@ -5,6 +5,7 @@ pub fn part1_main(args: &Vec<&String>) {
         panic!("Must provide the path of the file to open");
     }
     let path = args[0];
-    let result = Map::from_lines(get_lines(path)).half_distance();
+    let map = Map::from_lines(get_lines(path)).unwrap_or_else(|err| panic!("{err}"));
+    let result = map.half_distance();
     println!("{result}");
 }
\ No newline at end of file
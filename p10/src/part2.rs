@@ -1,11 +1,15 @@
-use crate::{get_lines::get_lines, map::Map};
+use crate::{get_lines::get_lines, map::{Map, AreaStrategy}};
 
 pub fn part2_main(args: &Vec<&String>) {
     if args.len() < 1 {
         panic!("Must provide the path of the file to open");
     }
     let path = args[0];
-    let result = Map::from_lines(get_lines(path)).to_loop_map().count_inside();
+    let strategy = args.get(1)
+        .map(|s| AreaStrategy::parse(s).unwrap_or_else(|| panic!("Unknown strategy {s}, use scanline, pick or flood")))
+        .unwrap_or(AreaStrategy::Scanline);
+    let map = Map::from_lines(get_lines(path)).unwrap_or_else(|err| panic!("{err}"));
+    let result = map.to_loop_map().count_inside(strategy);
 
     println!("{result}");
-}
\ No newline at end of file
+}
@@ -50,8 +50,8 @@ impl Tile {
             (Tile::Start, Tile::Start) => true,
             (Tile::Pipe(a1, a2), Tile::Start) => *a1 == direction || *a2 == direction,
             (Tile::Start, Tile::Pipe(b1, b2)) => *b1 == direction.reverse() || *b2 == direction.reverse(),
-            (Tile::Pipe(a1, a2), Tile::Pipe(b1, b2)) => 
-                *a1 == direction || *a2 == direction && *b1 == direction.reverse() || *b2 == direction.reverse(),
+            (Tile::Pipe(a1, a2), Tile::Pipe(b1, b2)) =>
+                (*a1 == direction || *a2 == direction) && (*b1 == direction.reverse() || *b2 == direction.reverse()),
         }
     }
 
@@ -67,4 +67,26 @@ impl Tile {
 pub enum LoopTile {
     Pipe(Direction, Direction),
     NotPipe
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::{Tile, Direction};
+
+    #[test]
+    fn vscode_help() { }
+
+    #[test_case('-', '-', Direction::East, true; "straight")]
+    #[test_case('F', 'J', Direction::East, true; "corners")]
+    #[test_case('|', '-', Direction::East, false; "no opening")]
+    #[test_case('-', '|', Direction::East, false; "no opening on other")]
+    #[test_case('L', '|', Direction::East, false; "one sided")]
+    #[test_case('|', '7', Direction::South, false; "one sided other")]
+    #[test_case('S', 'L', Direction::East, false; "start")]
+    #[test_case('S', 'J', Direction::East, true; "start connects")]
+    fn test_connect(a: char, b: char, direction: Direction, result: bool) {
+        assert_eq!(result, Tile::from_char(a).connect(&Tile::from_char(b), direction));
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{map::{Map, LoopMap, Coords}, pipe::{Tile, Direction}};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderStyle {
+    Plain,
+    Ansi
+}
+
+impl RenderStyle {
+    pub fn parse(s: &str) -> Option<RenderStyle> {
+        match s {
+            "plain" => Some(RenderStyle::Plain),
+            "ansi" => Some(RenderStyle::Ansi),
+            _ => None
+        }
+    }
+}
+
+const RESET: &str = "\x1b[0m";
+const LOOP: &str = "\x1b[1;33m";
+const START: &str = "\x1b[1;31m";
+const JUNK: &str = "\x1b[2m";
+const INSIDE: &str = "\x1b[42m";
+
+pub fn box_char(d1: Direction, d2: Direction) -> char {
+    let has = |d| d1 == d || d2 == d;
+    match (has(Direction::North), has(Direction::East), has(Direction::South), has(Direction::West)) {
+        (true, false, true, false) => '│',
+        (false, true, false, true) => '─',
+        (true, true, false, false) => '└',
+        (true, false, false, true) => '┘',
+        (false, false, true, true) => '┐',
+        (false, true, true, false) => '┌',
+        _ => panic!("Not a pipe: {d1:?} {d2:?}")
+    }
+}
+
+// Draws the maze with box characters. In plain text only the loop is drawn, with the start as S
+// and the enclosed tiles shaded; with ANSI colours the other pipes are shown dimmed as well.
+pub fn render(map: &Map, loop_map: &LoopMap, style: RenderStyle) -> String {
+    let inside = loop_map.inside_tiles();
+    let mut out = String::new();
+    for (y, inside_row) in inside.iter().enumerate() {
+        for (x, is_inside) in inside_row.iter().enumerate() {
+            let coords = Coords::new(x, y);
+            let on_loop = loop_map.is_loop(&coords);
+            let c = match map.index(&coords) {
+                Tile::Pipe(d1, d2) => box_char(*d1, *d2),
+                Tile::Start => 'S',
+                Tile::Ground => ' ',
+            };
+            match style {
+                RenderStyle::Plain => out.push(if coords == map.start() {
+                    'S'
+                }
+                else if on_loop {
+                    c
+                }
+                else if *is_inside {
+                    '▒'
+                }
+                else {
+                    ' '
+                }),
+                RenderStyle::Ansi => {
+                    let color = if coords == map.start() {
+                        START
+                    }
+                    else if on_loop {
+                        LOOP
+                    }
+                    else if *is_inside {
+                        INSIDE
+                    }
+                    else {
+                        JUNK
+                    };
+                    out += color;
+                    out.push(c);
+                    out += RESET;
+                }
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use crate::map::Map;
+    use super::{render, RenderStyle, RESET, LOOP, START, INSIDE};
+
+    #[test]
+    fn vscode_help() { }
+
+    #[test]
+    fn test_render_plain() {
+        let map = Map::from_lines("-F7.
+FS|.
+LJL7".lines().map(|l| l.to_string())).unwrap();
+        assert_eq!("    
+┌S  
+└┘  
+", render(&map, &map.to_loop_map(), RenderStyle::Plain));
+    }
+
+    #[test]
+    fn test_render_inside() {
+        let map = Map::from_lines("S--7
+|..|
+L--J".lines().map(|l| l.to_string())).unwrap();
+        assert_eq!("S──┐
+│▒▒│
+└──┘
+", render(&map, &map.to_loop_map(), RenderStyle::Plain));
+    }
+
+    #[test]
+    fn test_render_ansi() {
+        let map = Map::from_lines("S--7
+|..|
+L--J".lines().map(|l| l.to_string())).unwrap();
+        // The start is drawn as the pipe it stands for, in its own colour
+        let cell = |color: &str, c: char| format!("{color}{c}{RESET}");
+        let expected = [
+            [cell(START, '┌'), cell(LOOP, '─'), cell(LOOP, '─'), cell(LOOP, '┐')],
+            [cell(LOOP, '│'), cell(INSIDE, ' '), cell(INSIDE, ' '), cell(LOOP, '│')],
+            [cell(LOOP, '└'), cell(LOOP, '─'), cell(LOOP, '─'), cell(LOOP, '┘')],
+        ].map(|row| row.concat() + "\n").concat();
+        assert_eq!(expected, render(&map, &map.to_loop_map(), RenderStyle::Ansi));
+    }
+}
@@ -0,0 +1,21 @@
+use crate::{get_lines::get_lines, galaxy_map::GalaxyMap};
+
+pub fn dist_main(args: &Vec<&String>) {
+    if args.len() < 3 {
+        panic!("Must provide the path of the file to open and the numbers of the two galaxies");
+    }
+    let path = args[0];
+    // Galaxies are numbered from 1 as in the puzzle
+    let a: usize = args[1].parse().unwrap();
+    let b: usize = args[2].parse().unwrap();
+    let map = GalaxyMap::from_lines(get_lines(path)).unwrap_or_else(|err| panic!("{err}"));
+    if a == 0 || b == 0 || a > map.galaxy_count() || b > map.galaxy_count() {
+        panic!("Galaxies are numbered from 1 to {}", map.galaxy_count());
+    }
+    let linear = map.distance_between(a - 1, b - 1);
+
+    println!("{linear}");
+    if let Some(k) = args.get(3) {
+        println!("{}", linear.at(k.parse().unwrap()));
+    }
+}
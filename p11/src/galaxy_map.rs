@@ -1,36 +1,290 @@
+use std::fmt::Display;
+
 use crate::point::Point;
 
-pub fn get_galaxy_coords(lines: &mut impl Iterator<Item=String>, expand: i64) -> Vec<Point> {
-    let mut points = Vec::new();
-    let mut lines = lines.peekable();
-    let first_line = lines.peek().unwrap();
-    let mut empty_cols: Vec<_> = std::iter::repeat(true).take(first_line.len()).collect();
-
-    let mut y = 0;
-    lines.for_each(|line| {
-        let mut any_galaxies = false;
-        line.char_indices().for_each(|(index, c)| {
-            match c {
-                '#' => {
-                    any_galaxies = true;
-                    empty_cols[index] = false;
-                    points.push(Point::new(index as i64, y));
-                },
-                _ => ()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expansion {
+    // Every empty line becomes this many lines
+    Multiplicative(i64),
+    // Every empty line gets this many more lines next to it
+    Additive(i64)
+}
+
+impl Expansion {
+    // "x2" multiplies empty lines by two, "+1" adds one line to each. Lines cannot shrink away,
+    // so the factor must be at least 1 and the extra lines at least 0, and an empty line must
+    // still fit in an i64 once expanded.
+    pub fn parse(s: &str) -> Option<Expansion> {
+        let expansion = match s.split_at_checked(1)? {
+            ("x", factor) => factor.parse().ok().filter(|f| *f >= 1).map(Expansion::Multiplicative),
+            ("+", extra) => extra.parse().ok().filter(|e| *e >= 0).map(Expansion::Additive),
+            _ => None
+        }?;
+        expansion.size().map(|_| expansion)
+    }
+
+    fn size(&self) -> Option<i64> {
+        match self {
+            Expansion::Multiplicative(factor) => Some(*factor),
+            Expansion::Additive(extra) => extra.checked_add(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpansionModel {
+    pub rows: Expansion,
+    pub cols: Expansion
+}
+
+impl ExpansionModel {
+    pub fn uniform(expansion: Expansion) -> Self {
+        Self { rows: expansion, cols: expansion }
+    }
+
+    // The optional row and column arguments, like "x10" or "+9". Columns expand like rows unless
+    // given, and rows use `default` unless given.
+    pub fn parse_args(rows: Option<&str>, cols: Option<&str>, default: Expansion) -> Option<Self> {
+        let rows = rows.map_or(Some(default), Expansion::parse)?;
+        match cols {
+            Some(cols) => Some(Self { rows, cols: Expansion::parse(cols)? }),
+            None => Some(Self::uniform(rows))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapError {
+    Ragged { line: usize, expected: usize, found: usize }
+}
+
+impl Display for MapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MapError::Ragged { line, expected, found } =>
+                write!(f, "Line {line} has {found} columns but the first line has {expected}"),
+        }
+    }
+}
+
+// A distance that grows linearly with the expansion factor: constant + slope * k
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinearDistance {
+    pub constant: i64,
+    pub slope: i64
+}
+
+impl LinearDistance {
+    // Cannot overflow, as the product of two i64 fits in an i128 with room for the constant
+    pub fn at(&self, k: i64) -> i128 {
+        self.constant as i128 + self.slope as i128 * k as i128
+    }
+}
+
+impl Display for LinearDistance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} + {}k", self.constant, self.slope)
+    }
+}
+
+// The galaxies as they appear in the image, before any expansion
+pub struct GalaxyMap {
+    galaxies: Vec<Point>,
+    empty_rows: Vec<bool>,
+    empty_cols: Vec<bool>
+}
+
+impl GalaxyMap {
+    pub fn from_lines(lines: impl Iterator<Item=String>) -> Result<Self, MapError> {
+        let mut galaxies = Vec::new();
+        let mut empty_rows = Vec::new();
+        let mut empty_cols: Vec<bool> = Vec::new();
+        for (y, line) in lines.enumerate() {
+            if y == 0 {
+                empty_cols = vec![true; line.len()];
+            }
+            else if line.len() != empty_cols.len() {
+                return Err(MapError::Ragged { line: y, expected: empty_cols.len(), found: line.len() });
             }
-        });
-        y += if any_galaxies { 1 } else { expand } 
-    });
-
-    let mut x_coord_map: Vec<_> = (0..empty_cols.len()).map(|x| x as i64).collect();
-    let mut offset = 0;
-    x_coord_map.iter_mut().enumerate().for_each(|(index, x)| {
-        *x += offset;
-        if empty_cols[index] {
-            offset += expand - 1;
+            let mut any_galaxies = false;
+            line.char_indices().filter(|(_, c)| *c == '#').for_each(|(x, _)| {
+                any_galaxies = true;
+                empty_cols[x] = false;
+                galaxies.push(Point::new(x as i64, y as i64));
+            });
+            empty_rows.push(!any_galaxies);
         }
-    });
+        Ok(Self { galaxies, empty_rows, empty_cols })
+    }
+
+    // None if a line would land past i64::MAX. Only lines that exist need to fit, so the step
+    // past the last one is allowed to overflow.
+    fn coord_map(empty: &[bool], expansion: Expansion) -> Option<Vec<i64>> {
+        let mut at = Some(0i64);
+        empty.iter().map(|is_empty| {
+            let coord = at?;
+            let step = if *is_empty { expansion.size()? } else { 1 };
+            at = coord.checked_add(step);
+            Some(coord)
+        }).collect()
+    }
+
+    // None if the expanded map does not fit in i64 coordinates
+    pub fn expanded(&self, model: &ExpansionModel) -> Option<Vec<Point>> {
+        let x_map = Self::coord_map(&self.empty_cols, model.cols)?;
+        let y_map = Self::coord_map(&self.empty_rows, model.rows)?;
+        Some(self.galaxies.iter()
+            .map(|p| Point::new(x_map[p.x as usize], y_map[p.y as usize]))
+            .collect())
+    }
+
+    pub fn galaxy_count(&self) -> usize { self.galaxies.len() }
+
+    // Distance between two galaxies (numbered from 0 in reading order) when every empty
+    // row and column becomes k of them: the empty lines crossed scale with k, the rest do not
+    pub fn distance_between(&self, a: usize, b: usize) -> LinearDistance {
+        let (pa, pb) = (self.galaxies[a], self.galaxies[b]);
+        let crossed = |empty: &[bool], from: i64, to: i64| {
+            empty[from.min(to) as usize..from.max(to) as usize].iter().filter(|e| **e).count() as i64
+        };
+        let slope = crossed(&self.empty_cols, pa.x, pb.x) + crossed(&self.empty_rows, pa.y, pb.y);
+        LinearDistance { constant: pa.taxicab_dist(&pb) - slope, slope }
+    }
+}
+
+// Sum of the distance from each coordinate to all smaller ones: with the coordinates sorted,
+// c_i contributes c_i * i minus the sum of the i coordinates before it
+fn axis_distance_sum(mut coords: Vec<i64>) -> i128 {
+    coords.sort_unstable();
+    coords.iter().enumerate()
+        .fold((0i128, 0i128), |(total, prefix), (i, c)| {
+            let c = *c as i128;
+            (total + c * i as i128 - prefix, prefix + c)
+        }).0
+}
+
+// Sum of the taxicab distances between all pairs of points. The distance separates into
+// x and y, so each axis can be done on its own in O(n log n)
+pub fn total_distance(points: &[Point]) -> i128 {
+    axis_distance_sum(points.iter().map(|p| p.x).collect())
+        + axis_distance_sum(points.iter().map(|p| p.y).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::point::Point;
+    use super::{GalaxyMap, MapError, Expansion, ExpansionModel, total_distance};
+
+    #[test]
+    fn vscode_help() { }
+
+    fn total_distance_pairwise(points: &[Point]) -> i128 {
+        (0..points.len()).map(|first| {
+            (first+1..points.len()).map(|second|
+                points[first].taxicab_dist(&points[second]) as i128
+            ).sum::<i128>()
+        }).sum()
+    }
+
+    const EXAMPLE: &str = "...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+
+    fn example() -> GalaxyMap {
+        GalaxyMap::from_lines(EXAMPLE.lines().map(|l| l.to_string())).unwrap()
+    }
+
+    #[test_case(2, 374)]
+    #[test_case(10, 1030)]
+    #[test_case(100, 8410)]
+    #[test_case(1000000, 82000210)]
+    fn test_total_distance(expand: i64, result: i128) {
+        let galaxies = example().expanded(&ExpansionModel::uniform(Expansion::Multiplicative(expand))).unwrap();
+        assert_eq!(result, total_distance_pairwise(&galaxies));
+        assert_eq!(result, total_distance(&galaxies));
+    }
+
+    #[test_case(&[]; "empty")]
+    #[test_case(&[(3, -4)]; "single")]
+    #[test_case(&[(0, 0), (0, 0), (5, 5)]; "duplicates")]
+    #[test_case(&[(-7, 2), (13, -9), (4, 4), (0, 11), (-3, -3)]; "mixed")]
+    fn test_matches_pairwise(points: &[(i64, i64)]) {
+        let points: Vec<_> = points.iter().map(|(x, y)| Point::new(*x, *y)).collect();
+        assert_eq!(total_distance_pairwise(&points), total_distance(&points));
+    }
+
+    #[test_case(Expansion::Multiplicative(2), Expansion::Multiplicative(2), 374; "uniform")]
+    #[test_case(Expansion::Additive(1), Expansion::Additive(1), 374; "additive")]
+    #[test_case(Expansion::Additive(0), Expansion::Additive(0), 292; "none")]
+    #[test_case(Expansion::Multiplicative(2), Expansion::Multiplicative(1), 292 + 36; "rows only")]
+    #[test_case(Expansion::Multiplicative(1), Expansion::Multiplicative(2), 292 + 46; "cols only")]
+    fn test_per_axis(rows: Expansion, cols: Expansion, result: i128) {
+        assert_eq!(result, total_distance(&example().expanded(&ExpansionModel { rows, cols }).unwrap()));
+    }
+
+    #[test_case(4, 8, 9)]
+    #[test_case(0, 6, 15)]
+    #[test_case(2, 5, 17)]
+    #[test_case(7, 8, 5)]
+    fn test_distance_between(a: usize, b: usize, result: i128) {
+        let map = example();
+        let linear = map.distance_between(a, b);
+        assert_eq!(result, linear.at(2));
+        for k in [1, 10, 100, 1000000] {
+            let galaxies = map.expanded(&ExpansionModel::uniform(Expansion::Multiplicative(k))).unwrap();
+            assert_eq!(galaxies[a].taxicab_dist(&galaxies[b]) as i128, linear.at(k));
+        }
+        assert_eq!(linear.constant as i128 + linear.slope as i128 * i64::MAX as i128, linear.at(i64::MAX));
+    }
+
+    #[test_case("x2", Some(Expansion::Multiplicative(2)))]
+    #[test_case("+999999", Some(Expansion::Additive(999999)))]
+    #[test_case("2", None)]
+    #[test_case("", None; "empty")]
+    #[test_case("x", None; "no number")]
+    #[test_case("x-5", None; "negative factor")]
+    #[test_case("x0", None; "zero factor")]
+    #[test_case("x1", Some(Expansion::Multiplicative(1)); "unit factor")]
+    #[test_case("+-1", None; "negative extra")]
+    #[test_case("+0", Some(Expansion::Additive(0)); "no extra")]
+    #[test_case("+9223372036854775806", Some(Expansion::Additive(i64::MAX - 1)); "largest extra")]
+    #[test_case("+9223372036854775807", None; "extra too big")]
+    fn test_parse(s: &str, expansion: Option<Expansion>) {
+        assert_eq!(expansion, Expansion::parse(s));
+    }
+
+    #[test_case(None, None, Some((Expansion::Multiplicative(2), Expansion::Multiplicative(2))); "default")]
+    #[test_case(Some("+5"), None, Some((Expansion::Additive(5), Expansion::Additive(5))); "rows only")]
+    #[test_case(Some("+5"), Some("x3"), Some((Expansion::Additive(5), Expansion::Multiplicative(3))); "both")]
+    #[test_case(Some("x0"), None, None; "bad rows")]
+    #[test_case(Some("x2"), Some("x-5"), None; "bad cols")]
+    fn test_parse_args(rows: Option<&str>, cols: Option<&str>, result: Option<(Expansion, Expansion)>) {
+        let model = ExpansionModel::parse_args(rows, cols, Expansion::Multiplicative(2));
+        assert_eq!(result, model.map(|m| (m.rows, m.cols)));
+    }
+
+    #[test_case("x4611686018427387904", None; "too big")]
+    #[test_case("x3074457345618258601", None; "just too big")]
+    #[test_case("x3074457345618258600", Some(i64::MAX - 1); "largest")]
+    fn test_expanded_limit(expansion: &str, last_x: Option<i64>) {
+        // The last galaxy is in column 9, after three empty columns and six others
+        let model = ExpansionModel::uniform(Expansion::parse(expansion).unwrap());
+        let galaxies = example().expanded(&model);
+        assert_eq!(last_x, galaxies.map(|g| g.iter().map(|p| p.x).max().unwrap()));
+    }
 
-    points.iter_mut().for_each(|point| *point = Point::new(x_coord_map[point.x as usize], point.y));
-    points
-}
\ No newline at end of file
+    #[test]
+    fn test_ragged() {
+        let lines = ["#..", "..", "..#"].into_iter().map(|l| l.to_string());
+        assert_eq!(Some(MapError::Ragged { line: 1, expected: 3, found: 2 }), GalaxyMap::from_lines(lines).err());
+    }
+}
@@ -1,16 +1,17 @@
-use crate::{get_lines::get_lines, galaxy_map::get_galaxy_coords};
+use crate::{get_lines::get_lines, galaxy_map::{GalaxyMap, Expansion, ExpansionModel, total_distance}};
 
 pub fn part2_main(args: &Vec<&String>) {
     if args.len() < 1 {
         panic!("Must provide the path of the file to open");
     }
     let path = args[0];
-    let galaxies = get_galaxy_coords(&mut get_lines(path), 1000000);
-    let result: i64 = (0..galaxies.len() - 1).map(|first| {
-        (first+1..galaxies.len()).map(|second| 
-            galaxies[first].taxicab_dist(&galaxies[second])
-        ).sum::<i64>()
-    }).sum();
+    let map = GalaxyMap::from_lines(get_lines(path)).unwrap_or_else(|err| panic!("{err}"));
+    // Rows and columns can be expanded differently, like "x10" or "+9"
+    let model = ExpansionModel::parse_args(args.get(1).map(|s| s.as_str()), args.get(2).map(|s| s.as_str()), Expansion::Multiplicative(1000000))
+        .unwrap_or_else(|| panic!("Expansion must look like x1000000 or +999999, cannot shrink the empty lines, and must fit in an i64"));
+    let galaxies = map.expanded(&model)
+        .unwrap_or_else(|| panic!("The expanded map is too big for 64-bit coordinates"));
+    let result = total_distance(&galaxies);
 
     println!("{result}");
 }
\ No newline at end of file
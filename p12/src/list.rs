@@ -0,0 +1,12 @@
+use crate::{get_lines::get_lines, row::Row};
+
+pub fn list_main(args: &Vec<&String>) {
+    if args.len() < 2 {
+        panic!("Must provide the path of the file to open and the row number");
+    }
+    let path = args[0];
+    let index: usize = args[1].parse().unwrap();
+    let line = get_lines(path).nth(index).unwrap_or_else(|| panic!("There is no row {index}"));
+    let row = Row::from_line(line);
+    row.arrangements().for_each(|arrangement| println!("{arrangement}"));
+}
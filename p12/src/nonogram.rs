@@ -0,0 +1,338 @@
+use std::fmt::Display;
+
+use crate::row::{Row, Tile};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NonogramError {
+    MissingSize,
+    MissingClues { section: &'static str },
+    BadNumber(String),
+    WrongClueCount { section: &'static str, expected: usize, found: usize }
+}
+
+impl Display for NonogramError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NonogramError::MissingSize => write!(f, "The puzzle must give its width and height before its clues"),
+            NonogramError::MissingClues { section } => write!(f, "The puzzle has no {section} section"),
+            NonogramError::BadNumber(s) => write!(f, "Could not read a number from {s:?}"),
+            NonogramError::WrongClueCount { section, expected, found } =>
+                write!(f, "Expected {expected} {section} clues but found {found}"),
+        }
+    }
+}
+
+pub type Grid = Vec<Vec<Tile>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution {
+    Unsolvable,
+    Unique(Grid),
+    // Two different solutions, which is enough to show the puzzle is not unique
+    Multiple(Grid, Grid)
+}
+
+// feasible[i][j] is whether tiles[i..] can be filled with the runs clues[j..]. Unlike the
+// arrangement counts this cannot overflow, however long and open the line.
+fn feasible_table(row: &Row, working_before: &[usize]) -> Vec<Vec<bool>> {
+    let (tiles, clues) = (row.tiles(), row.clues());
+    let n = tiles.len();
+    let m = clues.len();
+    let mut feasible = vec![vec![false; m + 1]; n + 1];
+    feasible[n][m] = true;
+    for i in (0..n).rev() {
+        for j in 0..=m {
+            let working = tiles[i] != Tile::Broken && feasible[i + 1][j];
+            let broken = j < m && row.run_end(working_before, i, j).is_some_and(|end| feasible[(end + 1).min(n)][j + 1]);
+            feasible[i][j] = working || broken;
+        }
+    }
+    feasible
+}
+
+// The intersection of every arrangement: each tile that is the same in all of them becomes
+// known. Returns None if there is no arrangement at all.
+fn solve_line(row: &Row) -> Option<Vec<Tile>> {
+    let (tiles, clues) = (row.tiles(), row.clues());
+    let n = tiles.len();
+    let m = clues.len();
+    let working_before = row.working_before();
+    let feasible = feasible_table(row, &working_before);
+    if !feasible[0][0] {
+        return None;
+    }
+
+    // reachable[i][j]: tiles[..i] can be filled with clues[..j], ready for the next run at i
+    let mut reachable = vec![vec![false; m + 1]; n + 1];
+    reachable[0][0] = true;
+    let mut can_work = vec![false; n];
+    let mut can_break = vec![false; n];
+    for i in 0..n {
+        for j in 0..=m {
+            if !reachable[i][j] {
+                continue;
+            }
+            if tiles[i] != Tile::Broken && feasible[i + 1][j] {
+                can_work[i] = true;
+                reachable[i + 1][j] = true;
+            }
+            if j < m {
+                if let Some(end) = row.run_end(&working_before, i, j) {
+                    let next = (end + 1).min(n);
+                    if feasible[next][j + 1] {
+                        can_break[i..end].iter_mut().for_each(|b| *b = true);
+                        if end < n {
+                            can_work[end] = true;
+                        }
+                        reachable[next][j + 1] = true;
+                    }
+                }
+            }
+        }
+    }
+
+    Some(can_work.into_iter().zip(can_break).map(|(work, brk)| match (work, brk) {
+        (true, false) => Tile::Working,
+        (false, true) => Tile::Broken,
+        _ => Tile::Unknown
+    }).collect())
+}
+
+// A picture puzzle where every row and column is a line of springs with run-length clues
+pub struct Nonogram {
+    rows: Vec<Vec<usize>>,
+    cols: Vec<Vec<usize>>
+}
+
+fn parse_number(s: &str) -> Result<usize, NonogramError> {
+    s.trim().parse().map_err(|_| NonogramError::BadNumber(s.to_string()))
+}
+
+// Clues are separated by commas, and an empty line or 0 means an empty line
+fn parse_clues(line: &str) -> Result<Vec<usize>, NonogramError> {
+    let clues = line.split(',')
+        .filter(|s| !s.trim().is_empty())
+        .map(parse_number)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(clues.into_iter().filter(|c| *c > 0).collect())
+}
+
+impl Nonogram {
+    pub fn new(rows: Vec<Vec<usize>>, cols: Vec<Vec<usize>>) -> Self {
+        Self { rows, cols }
+    }
+
+    // The .non format: `width` and `height` lines, then `rows` and `columns` sections with one
+    // line of clues per row or column. Any other lines, like the title, are ignored.
+    pub fn parse(text: &str) -> Result<Self, NonogramError> {
+        let mut width = None;
+        let mut height = None;
+        let mut rows = None;
+        let mut cols = None;
+        let mut lines = text.lines();
+        while let Some(line) = lines.next() {
+            let mut parts = line.split_whitespace();
+            match parts.next() {
+                Some("width") => width = Some(parse_number(parts.next().unwrap_or(""))?),
+                Some("height") => height = Some(parse_number(parts.next().unwrap_or(""))?),
+                Some(section @ ("rows" | "columns")) => {
+                    let count = if section == "rows" { height } else { width }.ok_or(NonogramError::MissingSize)?;
+                    let clues = lines.by_ref().take(count).map(parse_clues).collect::<Result<Vec<_>, _>>()?;
+                    if clues.len() != count {
+                        return Err(NonogramError::WrongClueCount { section: if section == "rows" { "row" } else { "column" }, expected: count, found: clues.len() });
+                    }
+                    if section == "rows" { rows = Some(clues) } else { cols = Some(clues) }
+                },
+                _ => ()
+            }
+        }
+        match (rows, cols) {
+            (Some(rows), Some(cols)) => Ok(Self::new(rows, cols)),
+            (None, _) => Err(NonogramError::MissingClues { section: "rows" }),
+            (_, None) => Err(NonogramError::MissingClues { section: "columns" })
+        }
+    }
+
+    pub fn width(&self) -> usize { self.cols.len() }
+    pub fn height(&self) -> usize { self.rows.len() }
+
+    // Solves every row and column on its own until nothing changes. Returns false on a contradiction.
+    fn propagate(&self, grid: &mut Grid) -> bool {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (y, clues) in self.rows.iter().enumerate() {
+                match solve_line(&Row::new(grid[y].clone(), clues.clone())) {
+                    Some(line) => if line != grid[y] {
+                        grid[y] = line;
+                        changed = true;
+                    },
+                    None => return false
+                }
+            }
+            for (x, clues) in self.cols.iter().enumerate() {
+                let col: Vec<_> = grid.iter().map(|row| row[x]).collect();
+                match solve_line(&Row::new(col.clone(), clues.clone())) {
+                    Some(line) => if line != col {
+                        grid.iter_mut().zip(line).for_each(|(row, tile)| row[x] = tile);
+                        changed = true;
+                    },
+                    None => return false
+                }
+            }
+        }
+        true
+    }
+
+    // Propagates, then guesses the first unknown tile both ways when propagation stalls.
+    // Stops once two solutions are found.
+    fn search(&self, mut grid: Grid, found: &mut Vec<Grid>) {
+        if found.len() >= 2 || !self.propagate(&mut grid) {
+            return;
+        }
+        let unknown = grid.iter().enumerate()
+            .find_map(|(y, row)| row.iter().position(|t| *t == Tile::Unknown).map(|x| (x, y)));
+        match unknown {
+            Some((x, y)) => {
+                for guess in [Tile::Broken, Tile::Working] {
+                    let mut next = grid.clone();
+                    next[y][x] = guess;
+                    self.search(next, found);
+                }
+            },
+            None => found.push(grid)
+        }
+    }
+
+    pub fn solve(&self) -> Solution {
+        let mut found = Vec::new();
+        self.search(vec![vec![Tile::Unknown; self.width()]; self.height()], &mut found);
+        let mut found = found.into_iter();
+        match (found.next(), found.next()) {
+            (None, _) => Solution::Unsolvable,
+            (Some(grid), None) => Solution::Unique(grid),
+            (Some(first), Some(second)) => Solution::Multiple(first, second),
+        }
+    }
+}
+
+pub fn render(grid: &Grid) -> String {
+    grid.iter().map(|row| row.iter().map(|tile| match tile {
+        Tile::Working => '.',
+        Tile::Broken => '#',
+        Tile::Unknown => '?',
+    }).collect::<String>() + "\n").collect()
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::row::{Row, Tile};
+    use super::{Nonogram, NonogramError, Solution, render, solve_line};
+
+    #[test]
+    fn vscode_help() { }
+
+    #[test_case("???.### 1,1,3", Some("#.#.###"))]
+    #[test_case("?###???????? 3,2,1", Some(".###.???????"))]
+    #[test_case("?????????? 8", Some("??######??"))]
+    #[test_case("?.?#. 2", Some("..##."))]
+    #[test_case("#.# 2", None)]
+    fn test_solve_line(line: &str, result: Option<&str>) {
+        let row = Row::from_line(line.to_string());
+        let solved: Option<String> = solve_line(&row).map(|tiles| tiles.iter().map(|t| match t {
+            Tile::Working => '.',
+            Tile::Broken => '#',
+            Tile::Unknown => '?',
+        }).collect());
+        assert_eq!(result.map(|r| r.to_string()), solved);
+    }
+
+    #[test]
+    fn test_solve_long_line() {
+        // Far more arrangements than fit in a usize, but the start is still known
+        let row = Row::from_line(format!("#{} {}", "?".repeat(300), vec!["1"; 61].join(",")));
+        assert_eq!(None, row.possibilities());
+        let solved = solve_line(&row).unwrap();
+        assert_eq!(Tile::Broken, solved[0]);
+        assert_eq!(Tile::Working, solved[1]);
+        assert_eq!(Tile::Unknown, solved[2]);
+    }
+
+    #[test_case("title \"Plus\"
+width 3
+height 3
+
+rows
+1
+3
+1
+
+columns
+1
+3
+1
+", Some(".#.
+###
+.#.
+"); "unique")]
+    #[test_case("width 5
+height 5
+rows
+2
+2,1
+1,1
+3
+1,1
+columns
+2
+2,1
+1,2
+3
+1
+", Some(".##..
+##.#.
+#..#.
+.###.
+..#.#
+"); "needs the columns")]
+    #[test_case("width 2
+height 2
+rows
+2
+
+columns
+1
+0
+", None; "no solution")]
+    fn test_solve(text: &str, result: Option<&str>) {
+        let solution = Nonogram::parse(text).unwrap().solve();
+        match result {
+            Some(grid) => assert_eq!(Solution::Unique(grid.lines().map(|l| l.chars().map(crate::row::Tile::from_char).collect()).collect()), solution),
+            None => assert_eq!(Solution::Unsolvable, solution),
+        }
+    }
+
+    #[test]
+    fn test_multiple() {
+        // A diagonal can go either way
+        let nonogram = Nonogram::new(vec![vec![1], vec![1]], vec![vec![1], vec![1]]);
+        match nonogram.solve() {
+            Solution::Multiple(first, second) => {
+                assert_eq!("#.\n.#\n", render(&first));
+                assert_eq!(".#\n#.\n", render(&second));
+            },
+            other => panic!("Expected two solutions, got {other:?}")
+        }
+    }
+
+    #[test_case("rows\n1\n", NonogramError::MissingSize; "no size")]
+    #[test_case("width 1\nheight 1\ncolumns\n1\n", NonogramError::MissingClues { section: "rows" }; "no rows")]
+    #[test_case("width 1\nheight 1\nrows\n1\n", NonogramError::MissingClues { section: "columns" }; "no columns")]
+    #[test_case("width 1\nheight x\n", NonogramError::BadNumber("x".to_string()); "bad size")]
+    #[test_case("width 1\nheight 2\nrows\n1\n", NonogramError::WrongClueCount { section: "row", expected: 2, found: 1 }; "short")]
+    fn test_parse_error(text: &str, error: NonogramError) {
+        assert_eq!(Some(error), Nonogram::parse(text).err());
+    }
+}
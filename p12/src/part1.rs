@@ -1,13 +1,12 @@
-use crate::{get_lines::get_lines, row::Row, cache::new_cache};
+use crate::{get_lines::get_lines, row::Row};
 
 pub fn part1_main(args: &Vec<&String>) {
     if args.len() < 1 {
         panic!("Must provide the path of the file to open");
     }
     let path = args[0];
-    let mut cache = new_cache();
     let result: usize = get_lines(path)
-        .map(|line| Row::from_line(line).possibilities(&mut cache))
+        .map(|line| Row::from_line(line.clone()).possibilities().unwrap_or_else(|| panic!("Too many arrangements to count for {line}")))
         .sum();
 
     println!("{result}");
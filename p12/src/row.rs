@@ -1,9 +1,8 @@
-use std::ops::Range;
-
-use crate::{group_iter::GroupsIter, cache::{Cache, MyKey}};
+use crate::rng::Rng;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Tile {
+    Working,
     Broken,
     Unknown
 }
@@ -11,6 +10,7 @@ pub enum Tile {
 impl Tile {
     pub fn from_char(c: char) -> Self {
         match c {
+            '.' => Tile::Working,
             '#' => Tile::Broken,
             '?' => Tile::Unknown,
             _ => panic!()
@@ -20,14 +20,20 @@ impl Tile {
 
 #[derive(Debug)]
 pub struct Row {
-    tiles: Vec<Vec<Tile>>,
+    tiles: Vec<Tile>,
     clues: Vec<usize>
 }
 
 impl Row {
+    pub fn new(tiles: Vec<Tile>, clues: Vec<usize>) -> Self {
+        Self { tiles, clues }
+    }
+
+    pub fn tiles(&self) -> &[Tile] { &self.tiles }
+    pub fn clues(&self) -> &[usize] { &self.clues }
+
     fn from_parts(tile_part: &str, clue_part: &str) -> Self {
-        let tiles: Vec<Vec<_>> = tile_part.replace(".", " ").split_ascii_whitespace()
-            .map(|part| part.chars().map(Tile::from_char).collect()).collect();
+        let tiles = tile_part.chars().map(Tile::from_char).collect();
         let clues = clue_part.split(',').map(|s| s.parse().unwrap()).collect();
         Self { tiles, clues }
     }
@@ -39,95 +45,137 @@ impl Row {
 
     pub fn from_line_expanded(l: String, times: usize) -> Self {
         let parts: Vec<_> = l.split_ascii_whitespace().collect();
-        let tile_part = std::iter::repeat(parts[0]).take(times).fold(None, |acc, next| Some(match acc {
-            Some(s) => s + "?" + next,
-            None => next.to_string()
-        })).unwrap();
-        let clue_part = std::iter::repeat(parts[1]).take(times).fold(None, |acc, next| Some(match acc {
-            Some(s) => s + "," + next,
-            None => next.to_string()
-        })).unwrap();
+        let tile_part = vec![parts[0]; times].join("?");
+        let clue_part = vec![parts[1]; times].join(",");
         Self::from_parts(&tile_part, &clue_part)
     }
 
-    fn min_required(clues: &[usize]) -> usize {
-        if clues.len() == 0 { 0 } else { clues.iter().sum::<usize>() + clues.len() as usize - 1 }
+    // working_before[i] is the number of working tiles in tiles[..i]
+    pub fn working_before(&self) -> Vec<usize> {
+        let mut working_before = vec![0; self.tiles.len() + 1];
+        for (i, tile) in self.tiles.iter().enumerate() {
+            working_before[i + 1] = working_before[i] + if *tile == Tile::Working { 1 } else { 0 };
+        }
+        working_before
     }
 
-    fn count_possibilities_for_group_recursive(group: &[Tile], clues: &[usize]) -> usize {
-        if clues.len() == 0 {
-            if group.iter().any(|x| *x == Tile::Broken) {
-                0
-            }
-            else {
-                1
-            }
+    // Where the run for clues[j] ends if it can start at i: none of its tiles may be
+    // working and the tile after it, if any, must not be broken
+    pub fn run_end(&self, working_before: &[usize], i: usize, j: usize) -> Option<usize> {
+        let end = i + self.clues[j];
+        if end <= self.tiles.len() && working_before[end] == working_before[i] && (end == self.tiles.len() || self.tiles[end] != Tile::Broken) {
+            Some(end)
         }
         else {
-            let clue = clues[0];
-            let min_req = Self::min_required(clues) as usize;
-            if min_req > group.len() { return 0; }
-            let max = group.len() - min_req;
-            let mut result = 0;
-            for start_index in 0..=max {
-                let end_index = start_index + clue;
-                if (start_index == 0 || group[start_index - 1] == Tile::Unknown) && (end_index >= group.len() || group[end_index] == Tile::Unknown) {
-                    let next_start = if end_index + 1 < group.len() { end_index + 1 } else { end_index };
-                    result += Self::count_possibilities_for_group_recursive(&group[next_start..], &clues[1..]);
+            None
+        }
+    }
+
+    // counts[i][j] is the number of ways to fill tiles[i..] with the runs clues[j..],
+    // or None if some count does not fit in a usize
+    fn count_table(&self, working_before: &[usize]) -> Option<Vec<Vec<usize>>> {
+        let n = self.tiles.len();
+        let m = self.clues.len();
+        let mut counts = vec![vec![0usize; m + 1]; n + 1];
+        counts[n][m] = 1;
+        for i in (0..n).rev() {
+            for j in 0..=m {
+                let mut count = 0;
+                if self.tiles[i] != Tile::Broken {
+                    count = counts[i + 1][j];
                 }
-                if group[start_index] == Tile::Broken {
-                    break;
+                if j < m {
+                    if let Some(end) = self.run_end(working_before, i, j) {
+                        // The run is followed by a working tile unless it ends the row
+                        count = counts[(end + 1).min(n)][j + 1].checked_add(count)?;
+                    }
                 }
+                counts[i][j] = count;
             }
-            result
         }
+        Some(counts)
     }
 
-    fn count_possibilities_for_group(&self, index: usize, range: &Range<usize>, cache: &mut Cache) -> usize {
-        match cache.get(&(self.tiles[index].as_slice(), &self.clues[range.clone()]) as &dyn MyKey) {
-            Some(result) => *result,
-            None => {
-                let group = self.tiles[index].clone();
-                let clues: Vec<_> = self.clues[range.clone()].iter().map(|x| *x).collect();
-                let possibilities = Self::count_possibilities_for_group_recursive(&group, &clues);
-                cache.insert((group, clues), possibilities);
-                possibilities
-            }
-        }
+    // Listing and sampling arrangements by index needs every count exact
+    fn exact_count_table(&self, working_before: &[usize]) -> Vec<Vec<usize>> {
+        self.count_table(working_before).unwrap_or_else(|| panic!("The row has more arrangements than fit in a usize"))
+    }
+
+    // None if there are more arrangements than fit in a usize
+    pub fn possibilities(&self) -> Option<usize> {
+        self.count_table(&self.working_before()).map(|counts| counts[0][0])
     }
 
-    fn count_possibilities_for(&self, group_ranges: &Vec<Range<usize>>, cache: &mut Cache) -> usize {
-        group_ranges.iter().enumerate().rev().fold(1, |result, (index, range)| {
-            if result == 0 {
-                0
+    // Walks the count table, taking the working branch first, to find arrangement number `index`
+    fn arrangement_from(&self, working_before: &[usize], counts: &[Vec<usize>], mut index: usize) -> String {
+        let n = self.tiles.len();
+        let mut out = String::with_capacity(n);
+        let (mut i, mut j) = (0, 0);
+        while i < n {
+            let working = if self.tiles[i] != Tile::Broken { counts[i + 1][j] } else { 0 };
+            if index < working {
+                out.push('.');
+                i += 1;
+                continue;
             }
-            else {
-                result * self.count_possibilities_for_group(index, range, cache)
+            index -= working;
+            let end = self.run_end(working_before, i, j).unwrap();
+            out.extend(std::iter::repeat_n('#', end - i));
+            if end < n {
+                out.push('.');
             }
-        })
+            i = (end + 1).min(n);
+            j += 1;
+        }
+        out
+    }
+
+    // Arrangement number `index` in the order that puts working springs first
+    pub fn arrangement(&self, index: usize) -> Option<String> {
+        let working_before = self.working_before();
+        let counts = self.exact_count_table(&working_before);
+        if index < counts[0][0] {
+            Some(self.arrangement_from(&working_before, &counts, index))
+        }
+        else {
+            None
+        }
     }
 
-    pub fn possibilities(&self, cache: &mut Cache) -> usize {
-        let mut result = 0;
-        let mut group_iter = GroupsIter::new(&self.tiles, &self.clues);
+    pub fn arrangements(&self) -> impl Iterator<Item=String> + '_ {
+        let working_before = self.working_before();
+        let counts = self.exact_count_table(&working_before);
+        (0..counts[0][0]).map(move |index| self.arrangement_from(&working_before, &counts, index))
+    }
 
-        loop {
-            let g = group_iter.current(); 
-            result += self.count_possibilities_for(g, cache);
-            if !group_iter.next(&self.tiles, &self.clues) {
-                break;
-            }
+    // Every arrangement is equally likely since the index is uniform
+    pub fn sample(&self, rng: &mut Rng) -> Option<String> {
+        let total = self.exact_count_table(&self.working_before())[0][0];
+        if total == 0 {
+            None
+        }
+        else {
+            self.arrangement(rng.below(total as u64) as usize)
         }
-        
-        result
     }
-} 
+
+    // Whether a concrete arrangement fits the tiles and the clues
+    pub fn matches(&self, arrangement: &str) -> bool {
+        let fits_tiles = arrangement.len() == self.tiles.len() && arrangement.chars().zip(self.tiles.iter()).all(|(c, tile)| match tile {
+            Tile::Working => c == '.',
+            Tile::Broken => c == '#',
+            Tile::Unknown => c == '.' || c == '#',
+        });
+        let runs: Vec<usize> = arrangement.split('.').filter(|run| !run.is_empty()).map(|run| run.len()).collect();
+        fits_tiles && runs == self.clues
+    }
+}
 
 #[cfg(test)]
 mod test {
     use test_case::test_case;
-    use crate::cache::new_cache;
 
+    use crate::rng::Rng;
     use super::Row;
 
     #[test]
@@ -148,12 +196,48 @@ mod test {
     #[test_case("????.??# 1,1", 5)]
     #[test_case(".#?#???????.????# 1,2,3,2,1", 6)]
     fn test_possibilities(line: &str, possibilities: usize) {
-        assert_eq!(possibilities, Row::from_line(line.to_string()).possibilities(&mut new_cache()));
+        assert_eq!(Some(possibilities), Row::from_line(line.to_string()).possibilities());
+    }
+
+    #[test]
+    fn test_too_many_possibilities() {
+        let row = Row::from_line(format!("{} {}", "?".repeat(300), vec!["1"; 60].join(",")));
+        assert_eq!(None, row.possibilities());
+    }
+
+    #[test]
+    #[should_panic(expected = "more arrangements than fit")]
+    fn test_too_many_to_index() {
+        Row::from_line(format!("{} {}", "?".repeat(300), vec!["1"; 60].join(","))).arrangement(0);
     }
 
     #[test_case("???.### 1,1,3", 1)]
     #[test_case("?.??#??.?.????.??.?? 1,4,1,1,1,2", 452659840; "hard")]
     fn test_expanded(line: &str, possibilities: usize) {
-        assert_eq!(possibilities, Row::from_line_expanded(line.to_string(), 5).possibilities(&mut new_cache()));
+        assert_eq!(Some(possibilities), Row::from_line_expanded(line.to_string(), 5).possibilities());
+    }
+
+    #[test_case("???.### 1,1,3", &["#.#.###"])]
+    #[test_case("?###???????? 3,2,1", &[
+        ".###....##.#", ".###...##..#", ".###...##.#.", ".###..##...#", ".###..##..#.",
+        ".###..##.#..", ".###.##....#", ".###.##...#.", ".###.##..#..", ".###.##.#..."])]
+    #[test_case("#.# 2", &[])]
+    fn test_arrangements(line: &str, arrangements: &[&str]) {
+        let row = Row::from_line(line.to_string());
+        let all: Vec<_> = row.arrangements().collect();
+        assert_eq!(arrangements, all.as_slice());
+        assert_eq!(None, row.arrangement(arrangements.len()));
     }
-}
\ No newline at end of file
+
+    #[test_case("?.??#??.?.????.??.?? 1,4,1,1,1,2")]
+    #[test_case(".??..??...?##. 1,1,3")]
+    fn test_sample_expanded(line: &str) {
+        let row = Row::from_line_expanded(line.to_string(), 5);
+        let mut rng = Rng::new(12);
+        for _ in 0..100 {
+            assert!(row.matches(&row.sample(&mut rng).unwrap()));
+        }
+        let last = row.possibilities().unwrap() - 1;
+        assert!(row.matches(&row.arrangement(last).unwrap()));
+    }
+}
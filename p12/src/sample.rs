@@ -0,0 +1,24 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{get_lines::get_lines, row::Row, rng::Rng};
+
+pub fn sample_main(args: &Vec<&String>) {
+    if args.len() < 1 {
+        panic!("Must provide the path of the file to open");
+    }
+    let path = args[0];
+    let seed = args.get(1).map(|s| s.parse().unwrap())
+        .unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos() as u64);
+    let mut rng = Rng::new(seed);
+    // Prints one random arrangement of each unfolded row, checking it against the clues
+    get_lines(path).enumerate().for_each(|(index, line)| {
+        let row = Row::from_line_expanded(line, 5);
+        match row.sample(&mut rng) {
+            Some(arrangement) => {
+                assert!(row.matches(&arrangement), "Row {index} sampled {arrangement} which does not fit");
+                println!("{arrangement}");
+            },
+            None => println!("row {index} has no arrangements")
+        }
+    });
+}
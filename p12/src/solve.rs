@@ -0,0 +1,17 @@
+use std::fs;
+
+use crate::nonogram::{Nonogram, Solution, render};
+
+pub fn solve_main(args: &Vec<&String>) {
+    if args.len() < 1 {
+        panic!("Must provide the path of the .non file to open");
+    }
+    let path = args[0];
+    let text = fs::read_to_string(path).unwrap_or_else(|_| panic!("Could not open the given file"));
+    let nonogram = Nonogram::parse(&text).unwrap_or_else(|err| panic!("{err}"));
+    match nonogram.solve() {
+        Solution::Unsolvable => println!("No solution"),
+        Solution::Unique(grid) => print!("Unique solution\n{}", render(&grid)),
+        Solution::Multiple(first, second) => print!("Multiple solutions, for example\n{}\nand\n{}", render(&first), render(&second)),
+    }
+}
@@ -14,141 +14,154 @@ impl Tile {
     }
 }
 
-pub struct Grid {
-    rows: Vec<Vec<Tile>>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    // A mirror line between two rows
+    Horizontal,
+    // A mirror line between two columns
+    Vertical
 }
 
-fn error_match<T>(lhs: &[T], rhs: &[T], max_diff: usize) -> Option<usize> where T: Eq {
-    let mut diff = 0;
-    for (l, r) in lhs.iter().zip(rhs.iter()) {
-        if l != r {
-            diff += 1;
-            if diff > max_diff {
-                return None
-            }
-        }
-    }
-    Some(diff)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reflection {
+    pub axis: Axis,
+    // How many rows are above, or columns are left of, the mirror line
+    pub index: usize,
+    // The (x, y) of each tile to flip for the reflection to be exact, always on the top or left side
+    pub smudges: Vec<(usize, usize)>
 }
 
-impl Grid {
-    pub fn from_lines(lines: &mut impl Iterator<Item=String>) -> Self {
-        Self { rows: lines.map(|line| line.chars().map(Tile::from_char).collect()).collect() }
-    }
+impl Reflection {
+    pub fn smudge_count(&self) -> usize { self.smudges.len() }
 
-    pub fn transposed(&self) -> Self {
-        let cols = (0..self.rows[0].len()).map(|index| {
-            self.rows.iter().map(|row| row[index]).collect()
-        }).collect();
-        Self { rows: cols }
+    pub fn score(&self) -> usize {
+        match self.axis {
+            Axis::Horizontal => self.index * 100,
+            Axis::Vertical => self.index,
+        }
     }
+}
 
-    fn row_matches(&self, match_index: usize) -> impl Iterator<Item=usize> + '_ {
-        self.rows.iter().enumerate().filter_map(move |(index, row)| {
-            if (match_index % 2) != (index % 2) // disallow mirror axes on rows
-                && row == &self.rows[match_index] {
-                Some(index)
-            }
-            else {
-                None
-            }
-        })
-    }
+// Each row and column is kept as a bitmask with rocks set, so comparing two lines is an xor and a popcount
+pub struct Grid {
+    rows: Vec<u64>,
+    cols: Vec<u64>
+}
 
-    fn row_almost_matches(&self, match_index: usize) -> impl Iterator<Item=(usize, usize)> + '_ {
-        self.rows.iter().enumerate().filter_map(move |(index, row)| {
-            if (match_index % 2) != (index % 2) {
-                error_match(&row, &self.rows[match_index], 1).map(|diff| (index, diff))
-            }
-            else {
-                None
+// Every mirror line through `lines`, with the bits that differ between each mirrored pair of lines
+fn line_reflections(lines: &[u64], max_smudges: usize) -> impl Iterator<Item=(usize, Vec<(usize, u64)>)> + '_ {
+    (1..lines.len()).filter_map(move |index| {
+        let mut smudges = 0;
+        let mut diffs = Vec::new();
+        for (lower, upper) in (0..index).rev().zip(index..lines.len()) {
+            let diff = lines[lower] ^ lines[upper];
+            if diff != 0 {
+                smudges += diff.count_ones() as usize;
+                if smudges > max_smudges {
+                    return None;
+                }
+                diffs.push((lower, diff));
             }
-        })
-    }
+        }
+        Some((index, diffs))
+    })
+}
 
-    fn is_mirror_match_edge(&self, index_a: usize, index_b: usize) -> Option<usize> {
-        let min = index_a.min(index_b);
-        let max = index_a.max(index_b);
-        let midpoint = (min + max) / 2;
-        if (min+1..=midpoint).all(|lower| {
-            let upper = max - (lower - min);
-            &self.rows[lower] == &self.rows[upper]
-        }) {
-            Some(midpoint + 1)
+fn bits(mut mask: u64) -> impl Iterator<Item=usize> {
+    std::iter::from_fn(move || {
+        if mask == 0 {
+            None
         }
         else {
-            None
+            let bit = mask.trailing_zeros() as usize;
+            mask &= mask - 1;
+            Some(bit)
         }
-    }
+    })
+}
 
-    fn is_almost_mirror_match_edge(&self, index_a: usize, index_b: usize) -> Option<usize> {
-        let min = index_a.min(index_b);
-        let max = index_a.max(index_b);
-        let midpoint = (min + max) / 2;
-        let mut diff_left = 1;
-        let mut is_mirror = true;
-        for lower in min+1..=midpoint {
-            let upper = max - (lower - min);
-            match error_match(&self.rows[lower], &self.rows[upper], diff_left) {
-                Some(diff) => {
-                    diff_left -= diff;
-                },
-                None => {
-                    is_mirror = false;
-                    break;
-                }
-            }
-        }
-        if is_mirror && diff_left == 0 {
-            Some(midpoint + 1)
+impl Grid {
+    pub fn from_lines(lines: &mut impl Iterator<Item=String>) -> Self {
+        let tiles: Vec<Vec<Tile>> = lines.map(|line| line.chars().map(Tile::from_char).collect()).collect();
+        let width = tiles.first().map_or(0, |row| row.len());
+        // Each line is packed into a u64, one bit per tile
+        if let Some(y) = tiles.iter().position(|row| row.len() != width) {
+            panic!("Row {y} of the pattern is {} tiles wide but the first row is {width}", tiles[y].len());
         }
-        else {
-            None
+        if width > 64 || tiles.len() > 64 {
+            panic!("The pattern is {width}x{} but patterns wider or taller than 64 tiles are not supported", tiles.len());
         }
+        let rows = tiles.iter()
+            .map(|row| row.iter().enumerate().fold(0, |mask, (x, tile)| if *tile == Tile::Rocks { mask | 1 << x } else { mask }))
+            .collect();
+        let cols = (0..width)
+            .map(|x| tiles.iter().enumerate().fold(0, |mask, (y, row)| if row[x] == Tile::Rocks { mask | 1 << y } else { mask }))
+            .collect();
+        Self { rows, cols }
     }
 
-    fn mirrors_from(&self, edge_index: usize) -> Option<usize> {
-        self.row_matches(edge_index)
-            .filter_map(|index| self.is_mirror_match_edge(edge_index, index))
-            .nth(0)
-    }
-
-    fn almost_mirrors_from(&self, edge_index: usize) -> Option<usize> {
-        self.row_almost_matches(edge_index)
-            .filter_map(|(index, diff)| if diff == 1 {
-                self.is_mirror_match_edge(edge_index, index)
-            }
-            else {
-                self.is_almost_mirror_match_edge(edge_index, index)
-            })
-            .nth(0)
-    }
-
-    pub fn mirror_eval(&self) -> Option<usize> {
-        self.mirrors_from(0).or_else(|| self.mirrors_from(self.rows.len() - 1))
-    }
+    pub fn width(&self) -> usize { self.cols.len() }
+    pub fn height(&self) -> usize { self.rows.len() }
 
-    pub fn mirror_eval_with_transpose(&self) -> usize {
-        self.mirror_eval().map(|x| x * 100).or_else(|| self.transposed().mirror_eval()).unwrap()
+    pub fn is_rocks(&self, x: usize, y: usize) -> bool {
+        self.rows[y] & 1 << x != 0
     }
 
-    pub fn mirror_almost_eval(&self) -> Option<usize> {
-        self.almost_mirrors_from(0).or_else(|| self.almost_mirrors_from(self.rows.len() - 1))
+    // Every horizontal then vertical mirror line that needs at most `max_smudges` tiles flipped
+    pub fn find_reflections(&self, max_smudges: usize) -> Vec<Reflection> {
+        let horizontal = line_reflections(&self.rows, max_smudges).map(|(index, diffs)| Reflection {
+            axis: Axis::Horizontal,
+            index,
+            smudges: diffs.into_iter().flat_map(|(y, diff)| bits(diff).map(move |x| (x, y))).collect()
+        });
+        let vertical = line_reflections(&self.cols, max_smudges).map(|(index, diffs)| Reflection {
+            axis: Axis::Vertical,
+            index,
+            smudges: diffs.into_iter().flat_map(|(x, diff)| bits(diff).map(move |y| (x, y))).collect()
+        });
+        let mut reflections: Vec<_> = horizontal.chain(vertical).collect();
+        reflections.iter_mut().for_each(|r| r.smudges.sort_by_key(|&(x, y)| (y, x)));
+        reflections
     }
 
-    pub fn mirror_almost_eval_with_transpose(&self) -> usize {
-        self.mirror_almost_eval().map(|x| x * 100).or_else(|| self.transposed().mirror_almost_eval()).unwrap()
+    // The reflection with exactly `smudges` smudges, as the puzzle asks for
+    pub fn reflection(&self, smudges: usize) -> Option<Reflection> {
+        self.find_reflections(smudges).into_iter().find(|r| r.smudge_count() == smudges)
     }
 }
 
 #[cfg(test)]
 mod test {
     use test_case::test_case;
-    use super::Grid;
+    use super::{Grid, Axis};
 
     #[test]
     fn vscode_help() { }
 
+    fn grid(s: &str) -> Grid {
+        Grid::from_lines(&mut s.lines().map(|l| l.to_string()))
+    }
+
+    const EXAMPLE_1: &str = "#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.";
+
+    const EXAMPLE_2: &str = "#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#";
+
+    #[test_case(EXAMPLE_1, 0, 5)]
+    #[test_case(EXAMPLE_2, 0, 400)]
+    #[test_case(EXAMPLE_1, 1, 300)]
+    #[test_case(EXAMPLE_2, 1, 100)]
     #[test_case("..#.#......#.
 ###.#.####.#.
 ##.##.#..#.##
@@ -157,8 +170,44 @@ mod test {
 ###.##.##.##.
 ###..##..#...
 ###..##..##..
-####.#.##.#.#", 8)]
-    fn test_almost_mirror(s: &str, result: usize) {
-        assert_eq!(result, Grid::from_lines(&mut s.lines().map(|l| l.to_string())).mirror_almost_eval_with_transpose())
+####.#.##.#.#", 1, 8)]
+    fn test_reflection_score(s: &str, smudges: usize, result: usize) {
+        assert_eq!(Some(result), grid(s).reflection(smudges).map(|r| r.score()))
+    }
+
+    #[test]
+    fn test_find_reflections() {
+        let reflections = grid(EXAMPLE_1).find_reflections(1);
+        let found: Vec<_> = reflections.iter().map(|r| (r.axis, r.index, r.smudges.clone())).collect();
+        assert_eq!(vec![
+            (Axis::Horizontal, 3, vec![(0, 0)]),
+            (Axis::Vertical, 5, vec![]),
+        ], found);
     }
-}
\ No newline at end of file
+
+    #[test]
+    #[should_panic(expected = "The pattern is 65x1")]
+    fn test_too_wide() {
+        grid(&".".repeat(65));
+    }
+
+    #[test]
+    #[should_panic(expected = "The pattern is 1x65")]
+    fn test_too_tall() {
+        grid(&".\n".repeat(65));
+    }
+
+    #[test]
+    #[should_panic(expected = "Row 1 of the pattern is 65 tiles wide but the first row is 2")]
+    fn test_ragged() {
+        grid(&format!("..\n{}", ".".repeat(65)));
+    }
+
+    #[test]
+    fn test_two_smudges() {
+        // Flipping both corners of the top row makes it match the bottom row
+        let reflections = grid("#..#\n....").find_reflections(2);
+        assert!(reflections.iter().any(|r| r.axis == Axis::Horizontal && r.index == 1 && r.smudges == vec![(0, 0), (3, 0)]));
+        assert!(grid("#..#\n....").find_reflections(1).iter().all(|r| r.axis == Axis::Vertical));
+    }
+}
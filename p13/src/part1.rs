@@ -6,7 +6,7 @@ pub fn part1_main(args: &Vec<&String>) {
     }
     let path = args[0];
     let result: usize = get_line_groups(&mut get_lines(path))
-        .map(|lines| Grid::from_lines(&mut lines.into_iter()).mirror_eval_with_transpose())
+        .map(|lines| Grid::from_lines(&mut lines.into_iter()).reflection(0).unwrap().score())
         .sum();
 
     println!("{result}");
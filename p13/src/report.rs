@@ -0,0 +1,168 @@
+use crate::grid::{Axis, Grid, Reflection};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Text, Json
+}
+
+impl ReportFormat {
+    pub fn parse(s: &str) -> Option<ReportFormat> {
+        match s {
+            "text" => Some(ReportFormat::Text),
+            "json" => Some(ReportFormat::Json),
+            _ => None
+        }
+    }
+}
+
+// What one pattern contributed to the summary, with the pattern drawn so the mirror can be checked by eye
+pub struct PatternReport {
+    // Position of the pattern in the input, counting from 0
+    pub pattern: usize,
+    pub reflection: Option<Reflection>,
+    pub drawing: String
+}
+
+// Draws the pattern with the mirror marked as in the puzzle: `><` above and below a vertical mirror,
+// `v` and `^` either side of the rows around a horizontal one
+pub fn draw(grid: &Grid, reflection: Option<&Reflection>) -> String {
+    let tile = |x, y| if grid.is_rocks(x, y) { '#' } else { '.' };
+    match reflection {
+        Some(Reflection { axis: Axis::Vertical, index, .. }) => {
+            let marker = " ".repeat(index - 1) + "><\n";
+            let rows: String = (0..grid.height())
+                .map(|y| (0..grid.width()).map(|x| tile(x, y)).collect::<String>() + "\n")
+                .collect();
+            marker.clone() + &rows + &marker
+        },
+        Some(Reflection { axis: Axis::Horizontal, index, .. }) => (0..grid.height())
+            .map(|y| {
+                let marker = if y + 1 == *index { 'v' } else if y == *index { '^' } else { ' ' };
+                let row: String = (0..grid.width()).map(|x| tile(x, y)).collect();
+                format!("{marker}{row}{marker}\n")
+            })
+            .collect(),
+        None => (0..grid.height())
+            .map(|y| (0..grid.width()).map(|x| tile(x, y)).collect::<String>() + "\n")
+            .collect(),
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out += "\\\"",
+            '\\' => out += "\\\\",
+            '\n' => out += "\\n",
+            c => out.push(c),
+        }
+    }
+    out + "\""
+}
+
+impl PatternReport {
+    pub fn new(pattern: usize, grid: &Grid, smudges: usize) -> PatternReport {
+        let reflection = grid.reflection(smudges);
+        let drawing = draw(grid, reflection.as_ref());
+        PatternReport { pattern, reflection, drawing }
+    }
+
+    pub fn score(&self) -> usize {
+        self.reflection.as_ref().map_or(0, |r| r.score())
+    }
+
+    pub fn to_text(&self) -> String {
+        let summary = match &self.reflection {
+            Some(r) => {
+                let axis = match r.axis {
+                    Axis::Horizontal => format!("horizontal mirror below row {}", r.index - 1),
+                    Axis::Vertical => format!("vertical mirror right of column {}", r.index - 1),
+                };
+                let smudges = if r.smudges.is_empty() {
+                    String::from("no smudges")
+                }
+                else {
+                    let at: Vec<_> = r.smudges.iter().map(|(x, y)| format!("({x}, {y})")).collect();
+                    format!("smudges at {}", at.join(", "))
+                };
+                format!("{axis}, {smudges}, scores {}", self.score())
+            },
+            None => String::from("no reflection"),
+        };
+        format!("Pattern {}: {summary}\n{}", self.pattern, self.drawing)
+    }
+
+    pub fn to_json(&self) -> String {
+        let reflection = match &self.reflection {
+            Some(r) => {
+                let axis = match r.axis {
+                    Axis::Horizontal => "horizontal",
+                    Axis::Vertical => "vertical",
+                };
+                let smudges: Vec<_> = r.smudges.iter().map(|(x, y)| format!("[{x}, {y}]")).collect();
+                format!("{{\"axis\": \"{axis}\", \"index\": {}, \"smudges\": [{}]}}", r.index, smudges.join(", "))
+            },
+            None => String::from("null"),
+        };
+        format!("{{\"pattern\": {}, \"reflection\": {reflection}, \"score\": {}, \"drawing\": {}}}",
+            self.pattern, self.score(), json_string(&self.drawing))
+    }
+}
+
+pub fn write_reports(reports: &[PatternReport], format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Text => reports.iter().map(|r| r.to_text()).collect::<Vec<_>>().join("\n"),
+        ReportFormat::Json => {
+            let items: Vec<_> = reports.iter().map(|r| format!("  {}", r.to_json())).collect();
+            format!("[\n{}\n]\n", items.join(",\n"))
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::grid::Grid;
+    use super::PatternReport;
+
+    #[test]
+    fn vscode_help() { }
+
+    fn report(s: &str, smudges: usize) -> PatternReport {
+        PatternReport::new(0, &Grid::from_lines(&mut s.lines().map(|l| l.to_string())), smudges)
+    }
+
+    const EXAMPLE_1: &str = "#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.";
+
+    #[test_case(EXAMPLE_1, 0, "Pattern 0: vertical mirror right of column 4, no smudges, scores 5
+    ><
+#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+    ><
+"; "vertical")]
+    #[test_case(EXAMPLE_1, 1, "Pattern 0: horizontal mirror below row 2, smudges at (0, 0), scores 300
+ #.##..##. \n ..#.##.#. \nv##......#v\n^##......#^\n ..#.##.#. \n ..##..##. \n #.#.##.#. \n"; "horizontal")]
+    #[test_case("#.\n.#", 0, "Pattern 0: no reflection\n#.\n.#\n"; "none")]
+    fn test_text(s: &str, smudges: usize, result: &str) {
+        assert_eq!(result, report(s, smudges).to_text());
+    }
+
+    #[test]
+    fn test_json() {
+        assert_eq!("{\"pattern\": 0, \"reflection\": {\"axis\": \"horizontal\", \"index\": 1, \"smudges\": [[1, 0]]}, \"score\": 100, \"drawing\": \"v#.v\\n^##^\\n\"}",
+            report("#.\n##", 1).to_json());
+    }
+}
@@ -0,0 +1,18 @@
+use crate::{get_lines::get_lines, get_line_groups::get_line_groups, grid::Grid, report::{PatternReport, ReportFormat, write_reports}};
+
+pub fn report_main(args: &Vec<&String>) {
+    if args.len() < 1 {
+        panic!("Must provide the path of the file to open");
+    }
+    let path = args[0];
+    let smudges: usize = args.get(1).map_or(0, |s| s.parse().unwrap_or_else(|_| panic!("Could not read smudge count {s}")));
+    let format = args.get(2)
+        .map(|s| ReportFormat::parse(s).unwrap_or_else(|| panic!("Unknown format {s}, use text or json")))
+        .unwrap_or(ReportFormat::Text);
+    let reports: Vec<_> = get_line_groups(&mut get_lines(path))
+        .enumerate()
+        .map(|(pattern, lines)| PatternReport::new(pattern, &Grid::from_lines(&mut lines.into_iter()), smudges))
+        .collect();
+
+    print!("{}", write_reports(&reports, format));
+}
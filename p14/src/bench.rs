@@ -0,0 +1,102 @@
+use std::time::Instant;
+
+use crate::{get_lines::get_lines, grid::Grid, part2::load_after, cycle::{find_cycle_hashed, CycleMethod}, tile::Tile};
+
+// The platform as it was stored before the bitboard, kept to compare against
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct VecGrid {
+    data: Vec<Vec<Option<Tile>>>
+}
+
+impl VecGrid {
+    fn from_lines(lines: &mut impl Iterator<Item=String>) -> Self {
+        Self { data: lines.map(|line| line.chars().map(Tile::from_char).collect()).collect() }
+    }
+
+    // Tilts north and rotates clockwise into a new grid
+    fn slide_and_rotate(&self) -> Self {
+        let width = self.data[0].len();
+        let height = self.data.len();
+        let mut buf: Vec<Vec<Option<Tile>>> = (0..width).map(|_| (0..height).map(|_| None).collect()).collect();
+        let mut hit: Vec<_> = (0..width).map(|_| 0u32).collect();
+        for y in 0..height {
+            for x in 0..width {
+                match self.data[y][x] {
+                    Some(Tile::Circle) => {
+                        buf[x][height - 1 - hit[x] as usize] = Some(Tile::Circle);
+                        hit[x] += 1;
+                    },
+                    Some(Tile::Square) => {
+                        buf[x][height - 1 - y] = Some(Tile::Square);
+                        hit[x] = y as u32 + 1;
+                    }
+                    None => ()
+                }
+            }
+        }
+        Self { data: buf }
+    }
+
+    fn spin_cycle(&self) -> Self {
+        self.slide_and_rotate().slide_and_rotate().slide_and_rotate().slide_and_rotate()
+    }
+
+    fn weight(&self) -> usize {
+        let height = self.data.len();
+        self.data.iter().enumerate()
+            .map(|(y, row)| row.iter().filter(|t| **t == Some(Tile::Circle)).count() * (height - y))
+            .sum()
+    }
+}
+
+fn vec_load_after(grid: VecGrid, target: usize) -> usize {
+    let (cycle, history) = find_cycle_hashed(grid, VecGrid::spin_cycle);
+    history[cycle.index_of(target)].weight()
+}
+
+fn time_runs(runs: u32, mut f: impl FnMut() -> usize) -> (usize, f64) {
+    let start = Instant::now();
+    let result = (0..runs).map(|_| f()).last().unwrap();
+    (result, start.elapsed().as_secs_f64() * 1000.0 / runs as f64)
+}
+
+pub fn bench_main(args: &Vec<&String>) {
+    if args.len() < 1 {
+        panic!("Must provide the path of the file to open");
+    }
+    let path = args[0];
+    let runs: u32 = args.get(1).map_or(10, |s| s.parse().unwrap_or_else(|_| panic!("Could not read run count {s}")));
+    let lines: Vec<_> = get_lines(path).collect();
+    let vec_grid = VecGrid::from_lines(&mut lines.iter().cloned());
+    let grid = Grid::from_lines(&mut lines.iter().cloned());
+    let target = 1_000_000_000;
+
+    let (before, before_ms) = time_runs(runs, || vec_load_after(vec_grid.clone(), target));
+    let (after, after_ms) = time_runs(runs, || load_after(grid.clone(), CycleMethod::Hash, target));
+    if before != after {
+        panic!("The grids disagree: {before} before and {after} after");
+    }
+    println!("{after}");
+    println!("nested vectors {before_ms:.3}ms, bitboard {after_ms:.3}ms per run over {runs} runs");
+}
+
+#[cfg(test)]
+mod test {
+    use crate::grid::Grid;
+    use super::VecGrid;
+
+    #[test]
+    fn vscode_help() { }
+
+    #[test]
+    fn test_same_weights() {
+        let lines: Vec<_> = include_str!("test_input.txt").lines().map(|l| l.to_string()).collect();
+        let mut vec_grid = VecGrid::from_lines(&mut lines.iter().cloned());
+        let mut grid = Grid::from_lines(&mut lines.iter().cloned());
+        for _ in 0..20 {
+            vec_grid = vec_grid.spin_cycle();
+            grid.spin_cycle();
+            assert_eq!(vec_grid.weight(), grid.weight());
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::{collections::{HashMap, hash_map::DefaultHasher}, hash::{Hash, Hasher}};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CycleMethod {
+    Brent, Floyd, Hash
+}
+
+impl CycleMethod {
+    pub fn parse(s: &str) -> Option<CycleMethod> {
+        match s {
+            "brent" => Some(CycleMethod::Brent),
+            "floyd" => Some(CycleMethod::Floyd),
+            "hash" => Some(CycleMethod::Hash),
+            _ => None
+        }
+    }
+}
+
+// The shape of an iterated sequence: `prefix` states before the loop starts, then a loop of `period` states
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub prefix: usize,
+    pub period: usize
+}
+
+impl Cycle {
+    // The first iteration with the same state as iteration `target`
+    pub fn index_of(&self, target: usize) -> usize {
+        if target < self.prefix {
+            target
+        }
+        else {
+            self.prefix + (target - self.prefix) % self.period
+        }
+    }
+}
+
+// Brent's algorithm: keeps two states, and needs fewer steps than Floyd's
+pub fn brent<T: PartialEq + Clone>(start: T, step: impl Fn(&T) -> T) -> Cycle {
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    let mut tortoise = start.clone();
+    let mut hare = (0..period).fold(start, |state, _| step(&state));
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+    Cycle { prefix, period }
+}
+
+// Floyd's tortoise and hare: keeps two states, one moving twice as fast as the other
+pub fn floyd<T: PartialEq + Clone>(start: T, step: impl Fn(&T) -> T) -> Cycle {
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&step(&hare));
+    }
+
+    let mut tortoise = start;
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    let mut hare = step(&tortoise);
+    let mut period = 1;
+    while tortoise != hare {
+        hare = step(&hare);
+        period += 1;
+    }
+    Cycle { prefix, period }
+}
+
+fn fingerprint<T: Hash>(state: &T) -> u64 {
+    let mut s = DefaultHasher::new();
+    state.hash(&mut s);
+    s.finish()
+}
+
+// Keeps every state, so it steps the fewest times and gives back the states for looking up the answer.
+// Matching hashes are only candidates, and a cycle is only taken once the states are equal.
+pub fn find_cycle_hashed<T: Hash + Eq>(start: T, step: impl Fn(&T) -> T) -> (Cycle, Vec<T>) {
+    let mut seen: HashMap<u64, Vec<usize>> = HashMap::new();
+    let mut history = Vec::new();
+    let mut state = start;
+    loop {
+        let bucket = seen.entry(fingerprint(&state)).or_default();
+        if let Some(&first) = bucket.iter().find(|&&index| history[index] == state) {
+            return (Cycle { prefix: first, period: history.len() - first }, history);
+        }
+        bucket.push(history.len());
+        let next = step(&state);
+        history.push(state);
+        state = next;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::{brent, floyd, find_cycle_hashed, Cycle};
+
+    #[test]
+    fn vscode_help() { }
+
+    // Steps until a state repeats, to check the others against
+    fn brute_force(start: u64, step: impl Fn(&u64) -> u64) -> Cycle {
+        let mut history = vec![start];
+        loop {
+            let next = step(history.last().unwrap());
+            if let Some(prefix) = history.iter().position(|s| *s == next) {
+                return Cycle { prefix, period: history.len() - prefix };
+            }
+            history.push(next);
+        }
+    }
+
+    #[test_case(0, 1, 7; "small")]
+    #[test_case(3, 1, 255; "quadratic")]
+    #[test_case(5000, 3, 1009; "outside the loop")]
+    #[test_case(2, 0, 4; "fixed point")]
+    fn test_methods_agree(start: u64, add: u64, modulus: u64) {
+        let step = |x: &u64| (x * x + add) % modulus;
+        let expected = brute_force(start, step);
+        assert_eq!(expected, brent(start, step));
+        assert_eq!(expected, floyd(start, step));
+        let (cycle, history) = find_cycle_hashed(start, step);
+        assert_eq!(expected, cycle);
+        assert_eq!(cycle.prefix + cycle.period, history.len());
+    }
+
+    #[test_case(0, 0; "start")]
+    #[test_case(2, 2; "in prefix")]
+    #[test_case(3, 3; "cycle start")]
+    #[test_case(8, 3; "once round")]
+    #[test_case(1_000_000_000, 5; "far")]
+    fn test_index_of(target: usize, index: usize) {
+        // 0 1 2 then 3 4 5 6 7 forever
+        let step = |x: &usize| if *x == 7 { 3 } else { x + 1 };
+        let cycle = brent(0, step);
+        assert_eq!(Cycle { prefix: 3, period: 5 }, cycle);
+        assert_eq!(index, cycle.index_of(target));
+    }
+}
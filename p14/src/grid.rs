@@ -1,70 +1,229 @@
-use std::{collections::hash_map::DefaultHasher, hash::{Hasher, Hash}};
+use crate::{tile::Tile, load::LoadModel};
 
-use crate::tile::Tile;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    North, West, South, East
+}
 
+// Each row is a pair of bitmasks with bit x set for a round or cube rock in column x,
+// so tilting only moves bits around in place
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Grid {
-    data: Vec<Vec<Option<Tile>>>
+    width: usize,
+    round: Vec<u128>,
+    cube: Vec<u128>
 }
 
 impl Grid {
     pub fn from_lines(lines: &mut impl Iterator<Item=String>) -> Self {
-        Self { data: lines.map(|line| line.chars().map(Tile::from_char).collect()).collect() }
+        let mut width = 0;
+        let mut round = Vec::new();
+        let mut cube = Vec::new();
+        for (y, line) in lines.enumerate() {
+            if y == 0 {
+                width = line.len();
+            }
+            else if line.len() != width {
+                panic!("Line {y} of the platform is {} tiles wide but the first line is {width}", line.len());
+            }
+            if width > 128 {
+                panic!("Platforms wider than 128 tiles are not supported");
+            }
+            let (r, c) = line.chars().enumerate().fold((0, 0), |(r, c), (x, ch)| match Tile::from_char(ch) {
+                Some(Tile::Circle) => (r | 1 << x, c),
+                Some(Tile::Square) => (r, c | 1 << x),
+                None => (r, c)
+            });
+            round.push(r);
+            cube.push(c);
+        }
+        Self { width, round, cube }
     }
 
-    pub fn height(&self) -> usize { self.data.len() }
-    pub fn width(&self) -> usize { self.data[0].len() }
+    pub fn height(&self) -> usize { self.round.len() }
 
-    pub fn slide_and_rotate(&self) -> Self {
-        let width = self.width();
+    fn row_mask(&self) -> u128 {
+        if self.width == 128 { u128::MAX } else { (1 << self.width) - 1 }
+    }
+
+    // Moves every round rock one tile at a time until none can move
+    pub fn tilt(&mut self, direction: Direction) {
         let height = self.height();
-        let mut buf: Vec<Vec<Option<Tile>>> = (0..width).map(|_| (0..height).map(|_| None).collect()).collect();
-        let mut hit: Vec<_> = (0..width).map(|_| 0u32).collect();
-        for y in 0..height {
-            for x in 0..width {
-                match self.data[y][x] {
-                    Some(Tile::Circle) => {
-                        buf[x][height - 1 - hit[x] as usize] = Some(Tile::Circle);
-                        hit[x] += 1;
-                    },
-                    Some(Tile::Square) => {
-                        buf[x][height - 1 - y] = Some(Tile::Square);
-                        hit[x] = y as u32 + 1;
+        match direction {
+            Direction::North | Direction::South => {
+                let mut moved = true;
+                while moved {
+                    moved = false;
+                    for i in 1..height {
+                        let (from, to) = if direction == Direction::North { (i, i - 1) } else { (height - 1 - i, height - i) };
+                        let movable = self.round[from] & !(self.round[to] | self.cube[to]);
+                        if movable != 0 {
+                            self.round[from] &= !movable;
+                            self.round[to] |= movable;
+                            moved = true;
+                        }
                     }
-                    None => ()
                 }
-            }
+            },
+            Direction::West | Direction::East => {
+                let mask = self.row_mask();
+                for y in 0..height {
+                    let cube = self.cube[y];
+                    let round = &mut self.round[y];
+                    loop {
+                        let empty = !(*round | cube) & mask;
+                        let movable = if direction == Direction::West { *round & empty << 1 } else { *round & empty >> 1 };
+                        if movable == 0 {
+                            break;
+                        }
+                        *round &= !movable;
+                        *round |= if direction == Direction::West { movable >> 1 } else { movable << 1 };
+                    }
+                }
+            },
+        }
+    }
+
+    // Tilts north, west, south then east
+    pub fn spin_cycle(&mut self) {
+        for direction in [Direction::North, Direction::West, Direction::South, Direction::East] {
+            self.tilt(direction);
         }
-        Self { data: buf }
     }
 
     pub fn as_lines(&self) -> String {
-        self.data.iter().fold(String::new(), |s, l| { s + &l.iter().map(|t| match t {
-            Some(Tile::Circle) => 'O',
-            Some(Tile::Square) => '#',
-            None => '.'
+        (0..self.height()).fold(String::new(), |s, y| { s + &(0..self.width).map(|x| {
+            if self.round[y] & 1 << x != 0 { 'O' }
+            else if self.cube[y] & 1 << x != 0 { '#' }
+            else { '.' }
         }).collect::<String>() + "\n" })
     }
 
-    pub fn weight(&self) -> usize {
-        let mut sum = 0;
+    pub fn load(&self, model: &LoadModel) -> usize {
         let height = self.height();
-        for y in 0..height {
-            for x in 0..self.width() {
-                match self.data[y][x] {
-                    Some(Tile::Circle) => {
-                        sum += height - y;
-                    }
-                    _ => ()
-                }
-            }
-        }
-        sum
+        let line_count = match model.side {
+            Direction::North | Direction::South => height,
+            Direction::West | Direction::East => self.width,
+        };
+        self.round.iter().enumerate()
+            .map(|(y, row)| match model.side {
+                Direction::North => row.count_ones() as usize * model.weight(y, line_count),
+                Direction::South => row.count_ones() as usize * model.weight(height - 1 - y, line_count),
+                Direction::West => (0..self.width).filter(|x| row & 1 << x != 0).map(|x| model.weight(x, line_count)).sum(),
+                Direction::East => (0..self.width).filter(|x| row & 1 << x != 0).map(|x| model.weight(self.width - 1 - x, line_count)).sum(),
+            })
+            .sum()
     }
 
-    pub fn hash_u64(&self) -> u64 {
-        let mut s = DefaultHasher::new();
-        self.hash(&mut s);
-        s.finish()
+    // The load on the north side, as the puzzle asks for
+    pub fn weight(&self) -> usize {
+        self.load(&LoadModel::linear(Direction::North))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::{Grid, Direction};
+
+    #[test]
+    fn vscode_help() { }
+
+    const EXAMPLE: &str = "O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....";
+
+    fn grid(s: &str) -> Grid {
+        Grid::from_lines(&mut s.lines().map(|l| l.to_string()))
+    }
+
+    #[test_case(Direction::North, "OOOO.#.O..
+OO..#....#
+OO..O##..O
+O..#.OO...
+........#.
+..#....#.#
+..O..#.O.O
+..O.......
+#....###..
+#....#....
+"; "north")]
+    #[test_case(Direction::West, "O....#....
+OOO.#....#
+.....##...
+OO.#OO....
+OO......#.
+O.#O...#.#
+O....#OO..
+O.........
+#....###..
+#OO..#....
+"; "west")]
+    #[test_case(Direction::South, ".....#....
+....#....#
+...O.##...
+...#......
+O.O....O#O
+O.#..O.#.#
+O....#....
+OO....OO..
+#OO..###..
+#OO.O#...O
+"; "south")]
+    #[test_case(Direction::East, "....O#....
+.OOO#....#
+.....##...
+.OO#....OO
+......OO#.
+.O#...O#.#
+....O#..OO
+.........O
+#....###..
+#..OO#....
+"; "east")]
+    fn test_tilt(direction: Direction, result: &str) {
+        let mut grid = grid(EXAMPLE);
+        grid.tilt(direction);
+        assert_eq!(result, grid.as_lines());
+    }
+
+    #[test]
+    fn test_spin_cycle() {
+        let mut grid = grid(EXAMPLE);
+        grid.spin_cycle();
+        assert_eq!(".....#....
+....#...O#
+...OO##...
+.OO#......
+.....OOO#.
+.O#...O#.#
+....O#....
+......OOOO
+#...O###..
+#..OO#....
+", grid.as_lines());
+    }
+
+    #[test]
+    #[should_panic(expected = "Line 2 of the platform is 2 tiles wide but the first line is 3")]
+    fn test_ragged() {
+        grid("O..\n.#.\nO.");
+    }
+
+    #[test]
+    fn test_full_width() {
+        let mut grid = grid(&format!("{}O", ".".repeat(127)));
+        grid.tilt(Direction::West);
+        assert_eq!(1, grid.round[0]);
+        grid.tilt(Direction::East);
+        assert_eq!(1 << 127, grid.round[0]);
+    }
+}
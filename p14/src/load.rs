@@ -0,0 +1,112 @@
+use crate::{grid::{Grid, Direction}, cycle::find_cycle_hashed};
+
+// How much each round rock weighs on one side of the platform, by how many lines it is from that side
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadModel {
+    pub side: Direction,
+    // Weight of a rock in the line next to the side, then the line after, and so on.
+    // None means the puzzle's weights, the number of lines from the far side.
+    pub weights: Option<Vec<usize>>
+}
+
+impl LoadModel {
+    pub fn linear(side: Direction) -> LoadModel {
+        LoadModel { side, weights: None }
+    }
+
+    pub fn custom(side: Direction, weights: Vec<usize>) -> LoadModel {
+        LoadModel { side, weights: Some(weights) }
+    }
+
+    // Comma separated weights, such as `5,0,0,1`
+    pub fn parse_weights(side: Direction, s: &str) -> Option<LoadModel> {
+        let weights = s.split(',').map(|w| w.trim().parse().ok()).collect::<Option<Vec<_>>>()?;
+        Some(LoadModel::custom(side, weights))
+    }
+
+    // Lines beyond the end of custom weights weigh nothing
+    pub fn weight(&self, line: usize, line_count: usize) -> usize {
+        match &self.weights {
+            Some(weights) => weights.get(line).copied().unwrap_or(0),
+            None => line_count - line,
+        }
+    }
+}
+
+pub fn side_name(side: Direction) -> &'static str {
+    match side {
+        Direction::North => "north",
+        Direction::West => "west",
+        Direction::South => "south",
+        Direction::East => "east",
+    }
+}
+
+// One row per spin cycle from the start up to `cycles`, with whether it is before the loop or in it
+// and the load from each model
+pub fn load_history_csv(grid: &Grid, cycles: usize, models: &[(String, LoadModel)]) -> String {
+    let (cycle, _) = find_cycle_hashed(grid.clone(), |g| {
+        let mut next = g.clone();
+        next.spin_cycle();
+        next
+    });
+    let mut out = String::from("cycle,phase");
+    for (name, _) in models.iter() {
+        out += &format!(",{name}");
+    }
+    out += "\n";
+
+    let mut grid = grid.clone();
+    for n in 0..=cycles {
+        let phase = if n < cycle.prefix { "transient" } else { "periodic" };
+        out += &format!("{n},{phase}");
+        for (_, model) in models.iter() {
+            out += &format!(",{}", grid.load(model));
+        }
+        out += "\n";
+        grid.spin_cycle();
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::grid::{Grid, Direction};
+    use super::{LoadModel, load_history_csv};
+
+    #[test]
+    fn vscode_help() { }
+
+    fn grid(s: &str) -> Grid {
+        Grid::from_lines(&mut s.lines().map(|l| l.to_string()))
+    }
+
+    #[test_case(LoadModel::linear(Direction::North), 7; "north")]
+    #[test_case(LoadModel::linear(Direction::South), 5; "south")]
+    #[test_case(LoadModel::linear(Direction::West), 7; "west")]
+    #[test_case(LoadModel::linear(Direction::East), 5; "east")]
+    #[test_case(LoadModel::custom(Direction::North, vec![10]), 20; "only the top row")]
+    #[test_case(LoadModel::custom(Direction::East, vec![1, 0, 100]), 201; "custom east")]
+    fn test_load(model: LoadModel, result: usize) {
+        // Rocks at (0, 0), (2, 0) and (0, 2) on a 3x3 platform
+        assert_eq!(result, grid("O.O\n.#.\nO..").load(&model));
+    }
+
+    #[test]
+    fn test_parse_weights() {
+        assert_eq!(Some(LoadModel::custom(Direction::West, vec![5, 0, 1])), LoadModel::parse_weights(Direction::West, "5, 0,1"));
+        assert_eq!(None, LoadModel::parse_weights(Direction::West, "5,x"));
+    }
+
+    #[test]
+    fn test_history() {
+        let models = vec![("north".to_string(), LoadModel::linear(Direction::North))];
+        assert_eq!("cycle,phase,north
+0,transient,2
+1,periodic,1
+2,periodic,1
+", load_history_csv(&grid("O\n."), 2, &models));
+    }
+}
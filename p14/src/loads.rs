@@ -0,0 +1,20 @@
+use crate::{get_lines::get_lines, grid::{Grid, Direction}, load::{LoadModel, load_history_csv, side_name}};
+
+pub fn loads_main(args: &Vec<&String>) {
+    if args.len() < 2 {
+        panic!("Must provide the path of the file to open and the number of spin cycles");
+    }
+    let path = args[0];
+    let cycles: usize = args[1].parse().unwrap_or_else(|_| panic!("Could not read cycle count {}", args[1]));
+    let mut models: Vec<_> = [Direction::North, Direction::West, Direction::South, Direction::East].into_iter()
+        .map(|side| (side_name(side).to_string(), LoadModel::linear(side)))
+        .collect();
+    if let Some(weights) = args.get(2) {
+        let model = LoadModel::parse_weights(Direction::North, weights)
+            .unwrap_or_else(|| panic!("Could not read weights {weights}, use a comma separated list"));
+        models.push(("custom".to_string(), model));
+    }
+    let grid = Grid::from_lines(&mut get_lines(path));
+
+    print!("{}", load_history_csv(&grid, cycles, &models));
+}
@@ -2,22 +2,29 @@ use std::env;
 
 use part1::part1_main;
 use part2::part2_main;
+use bench::bench_main;
+use loads::loads_main;
 
 mod get_lines;
 mod part1;
 mod part2;
 mod tile;
 mod grid;
+mod cycle;
+mod bench;
+mod load;
+mod loads;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
         panic!("Must provide which part to use");
     }
-    let part: u32 = args[1].parse().unwrap();
-    match part {
-        1 => part1_main(&args[2..].into_iter().collect()),
-        2 => part2_main(&args[2..].into_iter().collect()),
-        _ => panic!("Please select part 1 or 2")
+    match args[1].as_str() {
+        "1" => part1_main(&args[2..].into_iter().collect()),
+        "2" => part2_main(&args[2..].into_iter().collect()),
+        "bench" => bench_main(&args[2..].into_iter().collect()),
+        "loads" => loads_main(&args[2..].into_iter().collect()),
+        _ => panic!("Please select part 1 or 2, bench or loads")
     };
 }
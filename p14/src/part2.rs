@@ -1,15 +1,26 @@
-use crate::{get_lines::get_lines, grid::Grid};
+use crate::{get_lines::get_lines, grid::Grid, cycle::{brent, floyd, find_cycle_hashed, CycleMethod}};
 
-fn find_cycle(history: &Vec<(u64, usize)>, lookback: usize) -> Option<usize> {
-    let lookback = lookback.min(history.len() - 1);
-    let last = history.last().unwrap();
-    for diff in 1..=lookback {
-        let index = history.len() - diff - 1;
-        if &history[index] == last {
-            return Some(diff);
-        }
+fn spun(grid: &Grid) -> Grid {
+    let mut next = grid.clone();
+    next.spin_cycle();
+    next
+}
+
+pub fn load_after(grid: Grid, method: CycleMethod, target: usize) -> usize {
+    match method {
+        CycleMethod::Hash => {
+            let (cycle, history) = find_cycle_hashed(grid, spun);
+            history[cycle.index_of(target)].weight()
+        },
+        CycleMethod::Brent | CycleMethod::Floyd => {
+            let cycle = if method == CycleMethod::Brent { brent(grid.clone(), spun) } else { floyd(grid.clone(), spun) };
+            let mut grid = grid;
+            for _ in 0..cycle.index_of(target) {
+                grid.spin_cycle();
+            }
+            grid.weight()
+        },
     }
-    None
 }
 
 pub fn part2_main(args: &Vec<&String>) {
@@ -17,27 +28,11 @@ pub fn part2_main(args: &Vec<&String>) {
         panic!("Must provide the path of the file to open");
     }
     let path = args[0];
-    let mut grid = Grid::from_lines(&mut get_lines(path));
-    let mut history = Vec::new();
-    history.push((0, 0));
-    let mut n = 0;
-    let target = 1_000_000_000;
-    let result = loop {
-        n += 1;
-        let next_grid = grid.slide_and_rotate().slide_and_rotate().slide_and_rotate().slide_and_rotate();
-        history.push((next_grid.hash_u64(), next_grid.weight()));
-        match find_cycle(&history, (n + 10) / 10) {
-            Some(diff) => {
-                let target_mod = target % diff;
-                let m = history.len() / diff;
-                let mut x = m * diff + target_mod;
-                if x >= history.len() { x -= diff; }
-                break history[x].1
-            },
-            None => ()
-        }
-        grid = next_grid;
-    };
+    let method = args.get(1)
+        .map(|s| CycleMethod::parse(s).unwrap_or_else(|| panic!("Unknown method {s}, use brent, floyd or hash")))
+        .unwrap_or(CycleMethod::Hash);
+    let grid = Grid::from_lines(&mut get_lines(path));
+    let result = load_after(grid, method, 1_000_000_000);
 
     println!("{result}");
-}
\ No newline at end of file
+}
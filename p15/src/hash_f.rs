@@ -1,3 +1,55 @@
+use std::hash::{Hasher, BuildHasher};
+
+// The puzzle's HASH algorithm. It is its own BuildHasher since every hash starts from zero.
+// Hashing through the Hash trait appends a terminator to strings, so write the bytes directly to get the puzzle's values.
+// Every byte counts, so newlines in the input are left out where it is read.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HolidayHasher {
+    state: u8
+}
+
+impl Hasher for HolidayHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes.iter() {
+            self.state = ((self.state as u16 + *b as u16) * 17 % 256) as u8;
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.state as u64
+    }
+}
+
+impl BuildHasher for HolidayHasher {
+    type Hasher = HolidayHasher;
+
+    fn build_hasher(&self) -> HolidayHasher {
+        HolidayHasher::default()
+    }
+}
+
 pub fn hash_f(s: &str) -> u8 {
-    s.chars().filter(|c| *c != '\n').fold(0, |acc, c| ((((acc as u16) + (c as u16)) * 17) % 256) as u8)
-}
\ No newline at end of file
+    let mut hasher = HolidayHasher::default().build_hasher();
+    hasher.write(s.as_bytes());
+    hasher.finish() as u8
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::hash_f;
+
+    #[test]
+    fn vscode_help() { }
+
+    #[test_case("HASH", 52)]
+    #[test_case("rn=1", 30)]
+    #[test_case("cm-", 253)]
+    #[test_case("rn", 0)]
+    #[test_case("qp", 1)]
+    #[test_case("\n", 170; "newline")]
+    fn test_hash_f(s: &str, result: u8) {
+        assert_eq!(result, hash_f(s));
+    }
+}
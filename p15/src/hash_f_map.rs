@@ -1,44 +1,96 @@
-use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
 
-use crate::hash_f::hash_f;
+use crate::hash_f::HolidayHasher;
 
-struct Lens {
-    pub label: String,
-    pub focal_len: u32
+// A map of 256 boxes chosen by the HASH of the key's bytes, where each box keeps its entries in insertion order
+pub struct HashFMap<K, V> {
+    hasher: HolidayHasher,
+    boxes: Vec<Vec<(K, V)>>
 }
 
-impl Lens {
-    fn focusing_power(&self, hash: u8, box_index: usize) -> u32 {
-        self.focal_len * (hash as u32 + 1) * (box_index as u32 + 1)
-    }
+impl<K: AsRef<[u8]>, V> Default for HashFMap<K, V> {
+    fn default() -> Self { Self::new() }
 }
 
-pub struct HashFMap {
-    boxes: HashMap<u8, Vec<Lens>>    
-}
+impl<K: AsRef<[u8]>, V> HashFMap<K, V> {
+    pub fn new() -> Self {
+        Self { hasher: HolidayHasher::default(), boxes: (0..256).map(|_| Vec::new()).collect() }
+    }
 
-impl HashFMap {
-    pub fn new() -> Self { Self { boxes: HashMap::new() }}
+    pub fn box_of(&self, key: impl AsRef<[u8]>) -> usize {
+        let mut hasher = self.hasher.build_hasher();
+        hasher.write(key.as_ref());
+        hasher.finish() as usize
+    }
 
-    pub fn sub(&mut self, label: &str) {
-        let hash = hash_f(label);
-        if let Some(b) = self.boxes.get_mut(&hash) {
-            if let Some((index, _)) = b.iter().enumerate().find(|(idx, lens)| lens.label == label) {
-                b.remove(index);
+    // Replaces the value in place if the key is already in its box, otherwise adds it to the back
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let b = self.box_of(&key);
+        match self.boxes[b].iter_mut().find(|(k, _)| k.as_ref() == key.as_ref()) {
+            Some((_, v)) => Some(std::mem::replace(v, value)),
+            None => {
+                self.boxes[b].push((key, value));
+                None
             }
         }
     }
 
-    pub fn eq(&mut self, label: &str, focal_len: u32) {
-        let hash = hash_f(label);
-        let b = self.boxes.entry(hash).or_insert_with(|| Vec::new());
-        match b.iter_mut().find(|lens| lens.label == label) {
-            Some (lens) => lens.focal_len = focal_len,
-            None => b.push(Lens { label: label.to_string(), focal_len })
-        }
+    // Takes the entry out of its box, moving the ones behind it forward
+    pub fn remove(&mut self, key: impl AsRef<[u8]>) -> Option<V> {
+        let b = self.box_of(&key);
+        let index = self.boxes[b].iter().position(|(k, _)| k.as_ref() == key.as_ref())?;
+        Some(self.boxes[b].remove(index).1)
+    }
+
+    pub fn get(&self, key: impl AsRef<[u8]>) -> Option<&V> {
+        self.boxes[self.box_of(&key)].iter()
+            .find(|(k, _)| k.as_ref() == key.as_ref())
+            .map(|(_, v)| v)
+    }
+
+    // Every box that has something in it, with its index
+    pub fn boxes(&self) -> impl Iterator<Item=(usize, &[(K, V)])> {
+        self.boxes.iter().enumerate()
+            .filter(|(_, b)| !b.is_empty())
+            .map(|(index, b)| (index, b.as_slice()))
     }
 
-    pub fn total_power(&self) -> u32 {
-        self.boxes.iter().map(|(hash, b)| b.iter().enumerate().map(|(box_index, l)| l.focusing_power(*hash, box_index)).sum::<u32>()).sum()
+    // Entries box by box, in insertion order within each box
+    pub fn iter(&self) -> impl Iterator<Item=(&K, &V)> {
+        self.boxes.iter().flat_map(|b| b.iter().map(|(k, v)| (k, v)))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::HashFMap;
+
+    #[test]
+    fn vscode_help() { }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut map = HashFMap::new();
+        assert_eq!(None, map.insert("rn", 1));
+        assert_eq!(None, map.insert("cm", 2));
+        assert_eq!(None, map.insert("qp", 3));
+        assert_eq!(Some(2), map.insert("cm", 4));
+        assert_eq!(Some(3), map.remove("qp"));
+        assert_eq!(None, map.remove("qp"));
+        assert_eq!(Some(&4), map.get("cm"));
+        assert_eq!(None, map.get("pc"));
+        // rn and cm both hash to 0, and cm kept its place when it was replaced
+        assert_eq!(vec![(&"rn", &1), (&"cm", &4)], map.iter().collect::<Vec<_>>());
+        assert_eq!(vec![0], map.boxes().map(|(index, _)| index).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_remove_keeps_order() {
+        let mut map = HashFMap::new();
+        for (key, value) in [("pc", 4), ("ot", 9), ("ab", 5)] {
+            map.insert(key.to_string(), value);
+        }
+        map.remove("pc");
+        assert_eq!(vec![("ot", 9), ("ab", 5)], map.boxes[3].iter().map(|(k, v)| (k.as_str(), *v)).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,16 @@
+use crate::part2::read_library;
+
+pub fn lens_main(args: &Vec<&String>) {
+    if args.len() < 1 {
+        panic!("Must provide the path of the file to open");
+    }
+    let path = args[0];
+    let library = read_library(path, false);
+    match args.get(1) {
+        Some(label) => match library.get(label.as_str()) {
+            Some(focal_len) => println!("{label} is in box {} with focal length {focal_len}", library.box_of(label.as_str())),
+            None => println!("There is no lens labelled {label}"),
+        },
+        None => library.iter().for_each(|(label, focal_len)| println!("{label} {focal_len}")),
+    }
+}
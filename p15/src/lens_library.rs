@@ -0,0 +1,38 @@
+use crate::hash_f_map::HashFMap;
+
+// Lens labels to focal lengths, with each box of the map being one of the boxes in the facility
+pub type LensLibrary = HashFMap<String, u32>;
+
+pub fn focusing_power(library: &LensLibrary) -> u32 {
+    library.boxes().fold(0, |acc, (index, lenses)| {
+        lenses.iter().enumerate().fold(acc, |acc, (slot, (_, focal_len))| {
+            acc + (index as u32 + 1) * (slot as u32 + 1) * focal_len
+        })
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{LensLibrary, focusing_power};
+
+    #[test]
+    fn vscode_help() { }
+
+    #[test]
+    fn test_focusing_power() {
+        // The worked example: rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7
+        let mut library = LensLibrary::new();
+        library.insert("rn".to_string(), 1);
+        library.remove("cm");
+        library.insert("qp".to_string(), 3);
+        library.insert("cm".to_string(), 2);
+        library.remove("qp");
+        library.insert("pc".to_string(), 4);
+        library.insert("ot".to_string(), 9);
+        library.insert("ab".to_string(), 5);
+        library.remove("pc");
+        library.insert("pc".to_string(), 6);
+        library.insert("ot".to_string(), 7);
+        assert_eq!(145, focusing_power(&library));
+    }
+}
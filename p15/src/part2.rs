@@ -1,25 +1,29 @@
-use std::{fs::File, io::Read};
+use crate::{lens_library::{LensLibrary, focusing_power}, step::{Step, read_steps, trace}};
 
-use crate::hash_f_map::HashFMap;
+// Runs the initialization sequence, printing the boxes after each step if tracing
+pub fn read_library(path: &String, tracing: bool) -> LensLibrary {
+    let mut library = LensLibrary::new();
+    for s in read_steps(path) {
+        let step = Step::parse(&s).unwrap_or_else(|err| panic!("{err}"));
+        step.apply(&mut library);
+        if tracing {
+            println!("After \"{step}\":\n{}", trace(&library));
+        }
+    }
+    library
+}
 
 pub fn part2_main(args: &Vec<&String>) {
     if args.len() < 1 {
         panic!("Must provide the path of the file to open");
     }
     let path = args[0];
-    let mut buf = String::new();
-    File::open(path).unwrap_or_else(|_| panic!("Could not open the given file")).read_to_string(&mut buf).unwrap();
-    let mut map = HashFMap::new();
-    buf.split(",").map(|s| s.replace('\n', "")).for_each(|s| {
-        if s.contains("=") {
-            let parts: Vec<_> = s.split("=").collect();
-            map.eq(parts[0], parts[1].parse().unwrap());
-        }
-        else {
-            map.sub(&s[0..s.len() - 1])
-        }
-    });
-    let result = map.total_power();
+    let tracing = match args.get(1) {
+        Some(flag) if flag.as_str() == "--trace" => true,
+        Some(flag) => panic!("Unknown option {flag}, only --trace is supported"),
+        None => false
+    };
+    let result = focusing_power(&read_library(path, tracing));
 
     println!("{result}");
-}
\ No newline at end of file
+}
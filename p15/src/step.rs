@@ -0,0 +1,144 @@
+use std::{fmt::Display, fs::File, io::{self, BufRead}};
+
+use crate::lens_library::LensLibrary;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    Insert { label: String, focal_len: u32 },
+    Remove { label: String }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepError {
+    NoOperation(String),
+    NoLabel(String),
+    BadFocalLength(String)
+}
+
+impl Display for StepError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StepError::NoOperation(s) => write!(f, "Step {s:?} has no = or - operation"),
+            StepError::NoLabel(s) => write!(f, "Step {s:?} has no label"),
+            StepError::BadFocalLength(s) => write!(f, "Step {s:?} does not have a focal length from 1 to 9"),
+        }
+    }
+}
+
+impl Step {
+    pub fn parse(s: &str) -> Result<Step, StepError> {
+        let (label, step) = if let Some((label, focal_len)) = s.split_once('=') {
+            let focal_len = match focal_len.parse() {
+                Ok(f) if (1..=9).contains(&f) => f,
+                _ => return Err(StepError::BadFocalLength(s.to_string())),
+            };
+            (label, Step::Insert { label: label.to_string(), focal_len })
+        }
+        else if let Some(label) = s.strip_suffix('-') {
+            (label, Step::Remove { label: label.to_string() })
+        }
+        else {
+            return Err(StepError::NoOperation(s.to_string()));
+        };
+        if label.is_empty() {
+            return Err(StepError::NoLabel(s.to_string()));
+        }
+        Ok(step)
+    }
+
+    pub fn apply(&self, library: &mut LensLibrary) {
+        match self {
+            Step::Insert { label, focal_len } => { library.insert(label.clone(), *focal_len); },
+            Step::Remove { label } => { library.remove(label); },
+        }
+    }
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Step::Insert { label, focal_len } => write!(f, "{label}={focal_len}"),
+            Step::Remove { label } => write!(f, "{label}-"),
+        }
+    }
+}
+
+// Reads the comma separated steps one at a time, ignoring line breaks, so the sequence never has to fit in memory
+pub struct StepReader<R> {
+    reader: R,
+    buf: Vec<u8>
+}
+
+impl<R: BufRead> StepReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self { reader, buf: Vec::new() }
+    }
+}
+
+pub fn read_steps(path: &String) -> StepReader<io::BufReader<File>> {
+    let file = File::open(path).unwrap_or_else(|_| panic!("Could not open the given file"));
+    StepReader::new(io::BufReader::new(file))
+}
+
+impl<R: BufRead> Iterator for StepReader<R> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.buf.clear();
+        self.reader.read_until(b',', &mut self.buf).unwrap();
+        let ended = self.buf.last() != Some(&b',');
+        let step: String = String::from_utf8_lossy(&self.buf).chars()
+            .filter(|c| *c != ',' && *c != '\n' && *c != '\r')
+            .collect();
+        if ended && step.is_empty() { None } else { Some(step) }
+    }
+}
+
+// The boxes with lenses in them, as in the puzzle's worked example
+pub fn trace(library: &LensLibrary) -> String {
+    library.boxes().fold(String::new(), |s, (index, lenses)| {
+        let lenses: Vec<_> = lenses.iter().map(|(label, focal_len)| format!("[{label} {focal_len}]")).collect();
+        s + &format!("Box {index}: {}\n", lenses.join(" "))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::lens_library::LensLibrary;
+    use super::{Step, StepError, StepReader, trace};
+
+    #[test]
+    fn vscode_help() { }
+
+    #[test_case("rn=1", Ok(Step::Insert { label: "rn".to_string(), focal_len: 1 }); "insert")]
+    #[test_case("cm-", Ok(Step::Remove { label: "cm".to_string() }); "remove")]
+    #[test_case("cm", Err(StepError::NoOperation("cm".to_string())); "no operation")]
+    #[test_case("=3", Err(StepError::NoLabel("=3".to_string())); "no label")]
+    #[test_case("-", Err(StepError::NoLabel("-".to_string())); "no label to remove")]
+    #[test_case("rn=", Err(StepError::BadFocalLength("rn=".to_string())); "no focal length")]
+    #[test_case("rn=10", Err(StepError::BadFocalLength("rn=10".to_string())); "focal length too long")]
+    fn test_parse(s: &str, result: Result<Step, StepError>) {
+        assert_eq!(result, Step::parse(s));
+    }
+
+    #[test_case("rn=1,cm-\n", &["rn=1", "cm-"]; "trailing newline")]
+    #[test_case("rn=1,c\nm-,qp=3", &["rn=1", "cm-", "qp=3"]; "split by a newline")]
+    #[test_case("rn=1,,cm-", &["rn=1", "", "cm-"]; "empty step")]
+    #[test_case("", &[]; "empty")]
+    fn test_reader(s: &str, steps: &[&str]) {
+        assert_eq!(steps, StepReader::new(s.as_bytes()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_trace() {
+        let mut library = LensLibrary::new();
+        for step in ["rn=1", "cm-", "qp=3", "cm=2", "qp-", "pc=4", "ot=9", "ab=5"] {
+            Step::parse(step).unwrap().apply(&mut library);
+        }
+        assert_eq!("Box 0: [rn 1] [cm 2]
+Box 3: [pc 4] [ot 9] [ab 5]
+", trace(&library));
+    }
+}
@@ -0,0 +1,29 @@
+use crate::{get_lines::get_lines, dig_instruction::{DigPlan, Interpretation}, dig::dig_count, polygon::{Polygon, split_loops}, compress::compressed_area};
+
+pub fn area_main(args: &Vec<&String>) {
+    if args.len() < 1 {
+        panic!("Must provide the path of the file to open");
+    }
+    let path = args[0];
+    let interpretation = args.get(1)
+        .map(|s| Interpretation::parse(s).unwrap_or_else(|| panic!("Unknown interpretation {s}, use written or colour")))
+        .unwrap_or(Interpretation::Written);
+    let plan = DigPlan::parse(get_lines(path)).unwrap_or_else(|err| panic!("{err}"));
+    let instructions = plan.instructions(interpretation).unwrap_or_else(|err| panic!("{err}"));
+    let loops = split_loops(instructions);
+    let polygons: Vec<_> = loops.iter().map(|instructions| Polygon::from_instructions(instructions)).collect();
+    for polygon in polygons.iter() {
+        if let Some(problem) = polygon.problems().first() {
+            panic!("{problem}");
+        }
+    }
+    let shoelace: Vec<_> = loops.iter().map(|instructions| dig_count(instructions)).collect();
+    let compressed = compressed_area(&polygons);
+
+    println!("shoelace {}, compressed {compressed}", shoelace.iter().map(|a| a.to_string()).collect::<Vec<_>>().join(" + "));
+    // Loops can overlap, so together they cover no more than their sum and no less than the largest
+    let (sum, largest) = (shoelace.iter().sum::<u64>(), shoelace.iter().copied().max().unwrap_or(0));
+    if compressed > sum || compressed < largest || (loops.len() == 1 && compressed != sum) {
+        panic!("The two areas disagree");
+    }
+}
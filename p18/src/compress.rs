@@ -0,0 +1,109 @@
+use crate::polygon::Polygon;
+
+// Index of the compressed row or column that starts at `at`
+fn index_of(lines: &[i64], at: i64) -> usize {
+    lines.binary_search(&at).unwrap()
+}
+
+// Counts every cell that is dug or walled in by any of the trenches, so loops may be separate or inside
+// one another. The flood fill runs on a grid with a row and column for each span between edge ends,
+// which keeps it small however long the edges are.
+pub fn compressed_area(polygons: &[Polygon]) -> u64 {
+    let edges: Vec<_> = polygons.iter().flat_map(|p| p.edges().iter().map(|e| e.bounds())).collect();
+    if edges.is_empty() {
+        return 0;
+    }
+    // Each edge covers the cells from its smallest corner up to one past its largest
+    let mut xs: Vec<i64> = edges.iter().flat_map(|(min, max)| [min.x, max.x + 1]).collect();
+    let mut ys: Vec<i64> = edges.iter().flat_map(|(min, max)| [min.y, max.y + 1]).collect();
+    for lines in [&mut xs, &mut ys] {
+        let (low, high) = (*lines.iter().min().unwrap(), *lines.iter().max().unwrap());
+        // A span of padding all round so the outside is connected
+        lines.extend([low - 1, high + 1]);
+        lines.sort();
+        lines.dedup();
+    }
+    let width = xs.len() - 1;
+    let height = ys.len() - 1;
+
+    let mut trench = vec![vec![false; width]; height];
+    for (min, max) in edges.iter() {
+        for row in trench[index_of(&ys, min.y)..index_of(&ys, max.y + 1)].iter_mut() {
+            row[index_of(&xs, min.x)..index_of(&xs, max.x + 1)].fill(true);
+        }
+    }
+
+    let mut outside = vec![vec![false; width]; height];
+    let mut to_explore = vec![(0usize, 0usize)];
+    outside[0][0] = true;
+    while let Some((x, y)) = to_explore.pop() {
+        let neighbors = [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)];
+        for (nx, ny) in neighbors {
+            if nx < width && ny < height && !trench[ny][nx] && !outside[ny][nx] {
+                outside[ny][nx] = true;
+                to_explore.push((nx, ny));
+            }
+        }
+    }
+
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .filter(|&(x, y)| !outside[y][x])
+        .map(|(x, y)| ((xs[x + 1] - xs[x]) * (ys[y + 1] - ys[y])) as u64)
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::{dig_instruction::{DigPlan, Interpretation}, dig::dig_count, polygon::{Polygon, split_loops}, vector2::Vector2};
+    use super::compressed_area;
+
+    #[test]
+    fn vscode_help() { }
+
+    fn polygon_at(x: i64, y: i64, s: &str) -> Polygon {
+        Polygon::from_instructions_at(Vector2::new(x, y), DigPlan::parse(s.lines()).unwrap().instructions(Interpretation::Written).unwrap())
+    }
+
+    const SQUARE: &str = "R 4 (#000000)\nD 4 (#000000)\nL 4 (#000000)\nU 4 (#000000)";
+
+    #[test_case(Interpretation::Written, 62; "part 1")]
+    #[test_case(Interpretation::Colour, 952408144115; "part 2")]
+    fn test_matches_shoelace(interpretation: Interpretation, result: u64) {
+        let plan = DigPlan::parse(include_str!("test_input.txt").lines()).unwrap();
+        let instructions = plan.instructions(interpretation).unwrap();
+        assert_eq!(result, dig_count(instructions));
+        assert_eq!(result, compressed_area(&[Polygon::from_instructions(instructions)]));
+    }
+
+    #[test_case(&[(0, 0)], 25; "one")]
+    #[test_case(&[(0, 0), (10, 0)], 50; "disjoint")]
+    #[test_case(&[(0, 0), (2, 2)], 41; "overlapping")]
+    #[test_case(&[(0, 0), (-4, -4)], 49; "sharing a corner")]
+    fn test_squares(starts: &[(i64, i64)], result: u64) {
+        let polygons: Vec<_> = starts.iter().map(|&(x, y)| polygon_at(x, y, SQUARE)).collect();
+        assert_eq!(result, compressed_area(&polygons));
+    }
+
+    #[test]
+    fn test_nested() {
+        let outer = polygon_at(0, 0, "R 10 (#000000)\nD 10 (#000000)\nL 10 (#000000)\nU 10 (#000000)");
+        let inner = polygon_at(3, 3, SQUARE);
+        assert_eq!(121, compressed_area(&[outer, inner]));
+    }
+
+    // Loops in one plan follow each other from the same start
+    #[test_case(&[SQUARE, "L 4 (#000000)\nU 4 (#000000)\nR 4 (#000000)\nD 4 (#000000)"], 2, 49; "sharing a corner")]
+    #[test_case(&["R 10 (#000000)\nD 10 (#000000)\nL 10 (#000000)\nU 10 (#000000)", SQUARE], 2, 121; "nested")]
+    #[test_case(&[SQUARE, SQUARE], 2, 25; "repeated")]
+    #[test_case(&[SQUARE, "R 2 (#000000)\nD 2 (#000000)"], 2, 25; "open tail")]
+    fn test_plan_loops(loops: &[&str], count: usize, result: u64) {
+        let plan = DigPlan::parse(loops.join("\n").lines()).unwrap();
+        let loops = split_loops(plan.instructions(Interpretation::Written).unwrap());
+        assert_eq!(count, loops.len());
+        let polygons: Vec<_> = loops.iter().map(|instructions| Polygon::from_instructions(instructions)).collect();
+        assert_eq!(result, compressed_area(&polygons));
+    }
+}
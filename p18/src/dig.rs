@@ -1,10 +1,10 @@
 use crate::{vector2::Vector2, dig_instruction::DigInstruction};
 
-pub fn dig_count(instructions: impl Iterator<Item=DigInstruction>) -> u64 {
+pub fn dig_count(instructions: &[DigInstruction]) -> u64 {
     let mut signed_area2 = 0i128;
     let mut boundary = 0u64;
     let mut at = Vector2::new(0, 0);
-    instructions.for_each(|instr| {
+    instructions.iter().for_each(|instr| {
         let vec = instr.direction.as_vector::<i128>() * (instr.count as i128);
         boundary += instr.count;
         let next = at + vec;
@@ -1,40 +1,184 @@
+use std::fmt::Display;
+
 use crate::direction::Direction;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8
+}
+
+impl Rgb {
+    // Reads `#rrggbb`
+    pub fn parse(s: &str) -> Option<Rgb> {
+        let hex = s.strip_prefix('#')?;
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let value = u32::from_str_radix(hex, 16).ok()?;
+        Some(Rgb { r: (value >> 16) as u8, g: (value >> 8) as u8, b: value as u8 })
+    }
+
+    pub fn value(&self) -> u32 {
+        (self.r as u32) << 16 | (self.g as u32) << 8 | self.b as u32
+    }
+}
+
+impl Display for Rgb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:06x}", self.value())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DigInstruction {
     pub direction: Direction,
     pub count: u64,
+    pub colour: Rgb
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpretation {
+    // The direction and count as written, for part 1
+    Written,
+    // The direction and count hidden in the colour, for part 2
+    Colour
+}
+
+impl Interpretation {
+    pub fn parse(s: &str) -> Option<Interpretation> {
+        match s {
+            "written" => Some(Interpretation::Written),
+            "colour" => Some(Interpretation::Colour),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanError {
+    MissingField(usize),
+    BadDirection(usize),
+    BadCount(usize),
+    BadColour(usize),
+    BadColourDirection(usize)
+}
+
+impl Display for PlanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlanError::MissingField(line) => write!(f, "Line {line} needs a direction, a count and a colour"),
+            PlanError::BadDirection(line) => write!(f, "Line {line} does not have a direction of U, R, D or L"),
+            PlanError::BadCount(line) => write!(f, "Line {line} does not have a count"),
+            PlanError::BadColour(line) => write!(f, "Line {line} does not have a colour like (#70c710)"),
+            PlanError::BadColourDirection(line) => write!(f, "The colour on line {line} does not end in a direction from 0 to 3"),
+        }
+    }
 }
 
-impl DigInstruction {
-    pub fn from_line(line: &str) -> DigInstruction {
-        let parts: Vec<_> = line.split(" ").collect();
-        let direction = match parts[0] {
-            "U" => Direction::North,
-            "R" => Direction::East,
-            "D" => Direction::South,
-            "L" => Direction::West,
-            _ => panic!()
-        };
-        let count = parts[1].parse().unwrap();
-        Self { direction, count }
-    }
-
-    pub fn from_hex(line: &str) -> DigInstruction {
-        let hex_part = line.split(" ").nth(2).unwrap();
-        let hex_part = &hex_part[2..hex_part.len()-1];
-        let hex_digits: Vec<_> = hex_part.chars().map(|c| c.to_digit(16).unwrap() as u64).collect();
-
-        let mut count = 0;
-        for digit in &hex_digits[0..5] {
-            count = (count * 16) + digit;
+// The whole dig plan read once, with both ways of reading each line. A plan can be valid as
+// written with colours that do not decode, so that only fails when the colours are asked for.
+pub struct DigPlan {
+    written: Vec<DigInstruction>,
+    decoded: Result<Vec<DigInstruction>, PlanError>
+}
+
+fn decode(line_number: usize, colour: Rgb) -> Result<DigInstruction, PlanError> {
+    // The first five hex digits are the count and the last is the direction
+    let direction = match colour.value() & 0xf {
+        0 => Direction::East,
+        1 => Direction::South,
+        2 => Direction::West,
+        3 => Direction::North,
+        _ => return Err(PlanError::BadColourDirection(line_number))
+    };
+    Ok(DigInstruction { direction, count: (colour.value() >> 4) as u64, colour })
+}
+
+fn parse_line(index: usize, line: &str) -> Result<(DigInstruction, Result<DigInstruction, PlanError>), PlanError> {
+    let line_number = index + 1;
+    let parts: Vec<_> = line.split_whitespace().collect();
+    if parts.len() != 3 {
+        return Err(PlanError::MissingField(line_number));
+    }
+    let direction = match parts[0] {
+        "U" => Direction::North,
+        "R" => Direction::East,
+        "D" => Direction::South,
+        "L" => Direction::West,
+        _ => return Err(PlanError::BadDirection(line_number))
+    };
+    let count = parts[1].parse().map_err(|_| PlanError::BadCount(line_number))?;
+    let colour = parts[2].strip_prefix('(').and_then(|s| s.strip_suffix(')'))
+        .and_then(Rgb::parse)
+        .ok_or(PlanError::BadColour(line_number))?;
+    Ok((DigInstruction { direction, count, colour }, decode(line_number, colour)))
+}
+
+impl DigPlan {
+    pub fn parse(lines: impl Iterator<Item=impl AsRef<str>>) -> Result<DigPlan, PlanError> {
+        let (written, decoded): (Vec<_>, Vec<_>) = lines.enumerate()
+            .map(|(index, line)| parse_line(index, line.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .unzip();
+        Ok(DigPlan { written, decoded: decoded.into_iter().collect() })
+    }
+
+    // Fails only for the colours, with the first line whose colour does not decode
+    pub fn instructions(&self, interpretation: Interpretation) -> Result<&[DigInstruction], PlanError> {
+        match interpretation {
+            Interpretation::Written => Ok(&self.written),
+            Interpretation::Colour => self.decoded.as_deref().map_err(|err| err.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::direction::Direction;
+    use super::{DigPlan, Interpretation, PlanError, Rgb};
+
+    #[test]
+    fn vscode_help() { }
+
+    #[test_case("#70c710", Some(Rgb { r: 0x70, g: 0xc7, b: 0x10 }); "colour")]
+    #[test_case("70c710", None; "no hash")]
+    #[test_case("#70c71", None; "short")]
+    #[test_case("#70c71g", None; "not hex")]
+    #[test_case("#+0c710", None; "sign")]
+    fn test_rgb(s: &str, result: Option<Rgb>) {
+        assert_eq!(result, Rgb::parse(s));
+        if let Some(rgb) = result {
+            assert_eq!(s, rgb.to_string());
         }
-        let direction = match hex_digits[5] {
-            0 => Direction::East,
-            1 => Direction::South,
-            2 => Direction::West,
-            3 => Direction::North,
-            _ => panic!()
-        };
-        Self { direction, count }
-    }
-}
\ No newline at end of file
+    }
+
+    #[test]
+    fn test_both_interpretations() {
+        let plan = DigPlan::parse(["R 6 (#70c710)", "D 5 (#0dc571)"].into_iter()).unwrap();
+        let written: Vec<_> = plan.instructions(Interpretation::Written).unwrap().iter().map(|i| (i.direction, i.count)).collect();
+        let decoded: Vec<_> = plan.instructions(Interpretation::Colour).unwrap().iter().map(|i| (i.direction, i.count)).collect();
+        assert_eq!(vec![(Direction::East, 6), (Direction::South, 5)], written);
+        assert_eq!(vec![(Direction::East, 461937), (Direction::South, 56407)], decoded);
+        assert_eq!(Rgb { r: 0x0d, g: 0xc5, b: 0x71 }, plan.instructions(Interpretation::Colour).unwrap()[1].colour);
+    }
+
+    #[test_case("R 6", PlanError::MissingField(2); "missing colour")]
+    #[test_case("X 6 (#70c710)", PlanError::BadDirection(2); "direction")]
+    #[test_case("R x (#70c710)", PlanError::BadCount(2); "count")]
+    #[test_case("R 6 #70c710", PlanError::BadColour(2); "no brackets")]
+    fn test_parse_error(line: &str, error: PlanError) {
+        assert_eq!(Some(error), DigPlan::parse(["R 1 (#000010)", line].into_iter()).err());
+    }
+
+    #[test]
+    fn test_undecodable_colour() {
+        let plan = DigPlan::parse(["R 1 (#000010)", "R 6 (#70c714)", "R 2 (#000025)"].into_iter()).unwrap();
+        assert_eq!(3, plan.instructions(Interpretation::Written).unwrap().len());
+        assert_eq!(Some(PlanError::BadColourDirection(2)), plan.instructions(Interpretation::Colour).err());
+    }
+}
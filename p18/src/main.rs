@@ -2,6 +2,9 @@ use std::env;
 
 use part1::part1_main;
 use part2::part2_main;
+use svg::svg_main;
+use raster::raster_main;
+use area::area_main;
 
 mod get_lines;
 mod part1;
@@ -10,16 +13,23 @@ mod direction;
 mod vector2;
 mod dig_instruction;
 mod dig;
+mod polygon;
+mod svg;
+mod raster;
+mod compress;
+mod area;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
         panic!("Must provide which part to use");
     }
-    let part: u32 = args[1].parse().unwrap();
-    match part {
-        1 => part1_main(&args[2..].into_iter().collect()),
-        2 => part2_main(&args[2..].into_iter().collect()),
-        _ => panic!("Please select part 1 or 2")
+    match args[1].as_str() {
+        "1" => part1_main(&args[2..].into_iter().collect()),
+        "2" => part2_main(&args[2..].into_iter().collect()),
+        "svg" => svg_main(&args[2..].into_iter().collect()),
+        "raster" => raster_main(&args[2..].into_iter().collect()),
+        "area" => area_main(&args[2..].into_iter().collect()),
+        _ => panic!("Please select part 1 or 2, svg, raster or area")
     };
 }
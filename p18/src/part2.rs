@@ -1,11 +1,16 @@
-use crate::{get_lines::get_lines, dig_instruction::DigInstruction, dig::dig_count};
+use crate::{get_lines::get_lines, dig_instruction::{DigPlan, Interpretation}, dig::dig_count, polygon::Polygon};
 
 pub fn part2_main(args: &Vec<&String>) {
     if args.len() < 1 {
         panic!("Must provide the path of the file to open");
     }
     let path = args[0];
-    let result = dig_count(get_lines(path).map(|line| DigInstruction::from_hex(&line)));
+    let plan = DigPlan::parse(get_lines(path)).unwrap_or_else(|err| panic!("{err}"));
+    let instructions = plan.instructions(Interpretation::Colour).unwrap_or_else(|err| panic!("{err}"));
+    if let Some(problem) = Polygon::from_instructions(instructions).problems().first() {
+        panic!("{problem}");
+    }
+    let result = dig_count(instructions);
 
     println!("{result}");
-}
\ No newline at end of file
+}
@@ -0,0 +1,244 @@
+use std::fmt::Display;
+
+use crate::{vector2::Vector2, direction::Direction, dig_instruction::{DigInstruction, Rgb}};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edge {
+    pub from: Vector2<i64>,
+    pub direction: Direction,
+    pub length: i64,
+    pub colour: Rgb
+}
+
+impl Edge {
+    pub fn to(&self) -> Vector2<i64> {
+        self.from + self.direction.as_vector::<i64>() * self.length
+    }
+
+    // The smallest and largest corners of the cells the edge digs through
+    pub fn bounds(&self) -> (Vector2<i64>, Vector2<i64>) {
+        let (a, b) = (self.from, self.to());
+        (Vector2::new(a.x.min(b.x), a.y.min(b.y)), Vector2::new(a.x.max(b.x), a.y.max(b.y)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolygonError {
+    NotClosed(Vector2<i64>),
+    ZeroLength(usize),
+    // The edge goes straight back along the one before it
+    Backtrack(usize),
+    // Two edges that are not next to each other share a cell
+    SelfIntersection(usize, usize)
+}
+
+impl Display for PolygonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolygonError::NotClosed(at) => write!(f, "The trench ends at ({}, {}) instead of where it started", at.x, at.y),
+            PolygonError::ZeroLength(i) => write!(f, "Instruction {} digs nothing", i + 1),
+            PolygonError::Backtrack(i) => write!(f, "Instruction {} digs back along the one before it", i + 1),
+            PolygonError::SelfIntersection(i, j) => write!(f, "Instructions {} and {} cross", i + 1, j + 1),
+        }
+    }
+}
+
+// The trench as a list of straight edges
+pub struct Polygon {
+    start: Vector2<i64>,
+    edges: Vec<Edge>
+}
+
+impl Polygon {
+    pub fn from_instructions(instructions: &[DigInstruction]) -> Polygon {
+        Polygon::from_instructions_at(Vector2::new(0, 0), instructions)
+    }
+
+    pub fn from_instructions_at(start: Vector2<i64>, instructions: &[DigInstruction]) -> Polygon {
+        let mut at = start;
+        let edges = instructions.iter()
+            .map(|instr| {
+                let edge = Edge { from: at, direction: instr.direction, length: instr.count as i64, colour: instr.colour };
+                at = edge.to();
+                edge
+            })
+            .collect();
+        Polygon { start, edges }
+    }
+
+    pub fn edges(&self) -> &[Edge] { &self.edges }
+
+    // Every reason the trench is not a simple closed loop
+    pub fn problems(&self) -> Vec<PolygonError> {
+        let mut problems = Vec::new();
+        let n = self.edges.len();
+        let end = self.edges.last().map_or(self.start, |last| last.to());
+        let closed = end == self.start;
+        if !closed {
+            problems.push(PolygonError::NotClosed(end));
+        }
+        for (i, edge) in self.edges.iter().enumerate() {
+            // Only a closed trench has the last edge before the first
+            let before = if i > 0 { Some(&self.edges[i - 1]) } else if closed && n > 1 { self.edges.last() } else { None };
+            if edge.length == 0 {
+                problems.push(PolygonError::ZeroLength(i));
+            }
+            else if before.is_some_and(|b| edge.direction == b.direction.reverse()) {
+                problems.push(PolygonError::Backtrack(i));
+            }
+        }
+        for i in 0..n {
+            for j in i + 2..n {
+                // The last edge meets the first at the start
+                if closed && i == 0 && j == n - 1 {
+                    continue;
+                }
+                let ((a_min, a_max), (b_min, b_max)) = (self.edges[i].bounds(), self.edges[j].bounds());
+                if a_min.x <= b_max.x && b_min.x <= a_max.x && a_min.y <= b_max.y && b_min.y <= a_max.y {
+                    problems.push(PolygonError::SelfIntersection(i, j));
+                }
+            }
+        }
+        problems
+    }
+
+    pub fn bounds(&self) -> (Vector2<i64>, Vector2<i64>) {
+        self.edges.iter().fold((self.start, self.start), |(min, max), edge| {
+            let (e_min, e_max) = edge.bounds();
+            (Vector2::new(min.x.min(e_min.x), min.y.min(e_min.y)), Vector2::new(max.x.max(e_max.x), max.y.max(e_max.y)))
+        })
+    }
+
+    // Digs out the lagoon cell by cell, or None if it would take more than `max_cells` cells
+    pub fn rasterise(&self, max_cells: usize) -> Option<Vec<Vec<bool>>> {
+        let (min, max) = self.bounds();
+        // One cell of padding all round so the outside is connected
+        let width = usize::try_from(max.x - min.x + 3).ok()?;
+        let height = usize::try_from(max.y - min.y + 3).ok()?;
+        if width.checked_mul(height)? > max_cells {
+            return None;
+        }
+        let mut trench = vec![vec![false; width]; height];
+        for edge in self.edges.iter() {
+            let step = edge.direction.as_vector::<i64>();
+            for i in 0..=edge.length {
+                let at = edge.from + step * i;
+                trench[(at.y - min.y + 1) as usize][(at.x - min.x + 1) as usize] = true;
+            }
+        }
+
+        let mut outside = vec![vec![false; width]; height];
+        let mut to_explore = vec![(0usize, 0usize)];
+        outside[0][0] = true;
+        while let Some((x, y)) = to_explore.pop() {
+            let neighbors = [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)];
+            for (nx, ny) in neighbors {
+                if nx < width && ny < height && !trench[ny][nx] && !outside[ny][nx] {
+                    outside[ny][nx] = true;
+                    to_explore.push((nx, ny));
+                }
+            }
+        }
+
+        Some(outside[1..height - 1].iter()
+            .map(|row| row[1..width - 1].iter().map(|o| !o).collect())
+            .collect())
+    }
+
+    // The trench drawn with each edge in the colour from its instruction
+    pub fn to_svg(&self) -> String {
+        let (min, max) = self.bounds();
+        let mut out = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">\n",
+            min.x - 1, min.y - 1, max.x - min.x + 2, max.y - min.y + 2);
+        for edge in self.edges.iter() {
+            let to = edge.to();
+            out += &format!("  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"1\" stroke-linecap=\"square\"/>\n",
+                edge.from.x, edge.from.y, to.x, to.y, edge.colour);
+        }
+        out += "</svg>\n";
+        out
+    }
+}
+
+// A plan can hold several loops one after another. Each loop ends back at the start, and the
+// next one begins there, so they all meet at that cell. Anything left after the last closed
+// loop is a final loop that does not close.
+pub fn split_loops(instructions: &[DigInstruction]) -> Vec<&[DigInstruction]> {
+    let start = Vector2::new(0, 0);
+    let mut loops = Vec::new();
+    let mut first = 0;
+    let mut at = start;
+    for (i, instr) in instructions.iter().enumerate() {
+        at += instr.direction.as_vector::<i64>() * instr.count as i64;
+        if at == start && instructions[first..=i].iter().any(|instr| instr.count > 0) {
+            loops.push(&instructions[first..=i]);
+            first = i + 1;
+        }
+    }
+    if first < instructions.len() {
+        loops.push(&instructions[first..]);
+    }
+    loops
+}
+
+pub fn render_raster(raster: &[Vec<bool>]) -> String {
+    raster.iter().map(|row| row.iter().map(|dug| if *dug { '#' } else { '.' }).collect::<String>() + "\n").collect()
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::{dig_instruction::{DigPlan, Interpretation}, dig::dig_count};
+    use super::{Polygon, PolygonError, render_raster};
+
+    #[test]
+    fn vscode_help() { }
+
+    fn polygon(s: &str) -> Polygon {
+        Polygon::from_instructions(DigPlan::parse(s.lines()).unwrap().instructions(Interpretation::Written).unwrap())
+    }
+
+    #[test_case(include_str!("test_input.txt"), &[]; "example")]
+    #[test_case("R 2 (#000000)\nD 2 (#000000)\nL 2 (#000000)", &[PolygonError::NotClosed(crate::vector2::Vector2::new(0, 2))]; "not closed")]
+    #[test_case("R 2 (#000000)\nD 0 (#000000)\nD 2 (#000000)\nL 2 (#000000)\nU 2 (#000000)", &[PolygonError::ZeroLength(1), PolygonError::SelfIntersection(0, 2)]; "zero length")]
+    #[test_case("R 2 (#000000)\nL 1 (#000000)\nD 2 (#000000)\nL 1 (#000000)\nU 2 (#000000)", &[PolygonError::Backtrack(1), PolygonError::SelfIntersection(0, 2)]; "backtrack")]
+    #[test_case("R 4 (#000000)
+D 2 (#000000)
+L 2 (#000000)
+U 4 (#000000)
+L 2 (#000000)
+D 2 (#000000)", &[PolygonError::SelfIntersection(0, 3)]; "figure of eight")]
+    fn test_problems(s: &str, problems: &[PolygonError]) {
+        assert_eq!(problems, polygon(s).problems());
+    }
+
+    #[test]
+    fn test_rasterise() {
+        let s = include_str!("test_input.txt");
+        let raster = polygon(s).rasterise(1000).unwrap();
+        assert_eq!("#######
+#######
+#######
+..#####
+..#####
+#######
+#####..
+#######
+.######
+.######
+", render_raster(&raster));
+        let dug = raster.iter().flatten().filter(|d| **d).count() as u64;
+        assert_eq!(dig_count(DigPlan::parse(s.lines()).unwrap().instructions(Interpretation::Written).unwrap()), dug);
+        assert_eq!(None, polygon(s).rasterise(10));
+    }
+
+    #[test]
+    fn test_svg() {
+        assert_eq!("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"-1 -1 4 3\">
+  <line x1=\"0\" y1=\"0\" x2=\"2\" y2=\"0\" stroke=\"#70c710\" stroke-width=\"1\" stroke-linecap=\"square\"/>
+  <line x1=\"2\" y1=\"0\" x2=\"2\" y2=\"1\" stroke=\"#0dc571\" stroke-width=\"1\" stroke-linecap=\"square\"/>
+</svg>
+", polygon("R 2 (#70c710)\nD 1 (#0dc571)").to_svg());
+    }
+}
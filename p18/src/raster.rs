@@ -0,0 +1,23 @@
+use crate::{get_lines::get_lines, dig_instruction::{DigPlan, Interpretation}, dig::dig_count, polygon::{Polygon, render_raster}};
+
+pub fn raster_main(args: &Vec<&String>) {
+    if args.len() < 1 {
+        panic!("Must provide the path of the file to open");
+    }
+    let path = args[0];
+    let max_cells: usize = args.get(1).map_or(1_000_000, |s| s.parse().unwrap_or_else(|_| panic!("Could not read cell limit {s}")));
+    let interpretation = args.get(2)
+        .map(|s| Interpretation::parse(s).unwrap_or_else(|| panic!("Unknown interpretation {s}, use written or colour")))
+        .unwrap_or(Interpretation::Written);
+    let plan = DigPlan::parse(get_lines(path)).unwrap_or_else(|err| panic!("{err}"));
+    let instructions = plan.instructions(interpretation).unwrap_or_else(|err| panic!("{err}"));
+    let polygon = Polygon::from_instructions(instructions);
+    for problem in polygon.problems() {
+        println!("{problem}");
+    }
+    let raster = polygon.rasterise(max_cells).unwrap_or_else(|| panic!("The lagoon is bigger than {max_cells} cells"));
+    let dug = raster.iter().flatten().filter(|d| **d).count();
+
+    print!("{}", render_raster(&raster));
+    println!("raster {dug}, pick {}", dig_count(instructions));
+}
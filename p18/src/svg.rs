@@ -0,0 +1,19 @@
+use crate::{get_lines::get_lines, dig_instruction::{DigPlan, Interpretation}, polygon::Polygon};
+
+pub fn svg_main(args: &Vec<&String>) {
+    if args.len() < 1 {
+        panic!("Must provide the path of the file to open");
+    }
+    let path = args[0];
+    let interpretation = args.get(1)
+        .map(|s| Interpretation::parse(s).unwrap_or_else(|| panic!("Unknown interpretation {s}, use written or colour")))
+        .unwrap_or(Interpretation::Written);
+    let plan = DigPlan::parse(get_lines(path)).unwrap_or_else(|err| panic!("{err}"));
+    let instructions = plan.instructions(interpretation).unwrap_or_else(|err| panic!("{err}"));
+    let polygon = Polygon::from_instructions(instructions);
+    for problem in polygon.problems() {
+        eprintln!("{problem}");
+    }
+
+    print!("{}", polygon.to_svg());
+}
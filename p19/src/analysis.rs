@@ -0,0 +1,218 @@
+use std::{collections::{BTreeMap, BTreeSet, HashSet}, fmt::Display};
+
+use crate::{workflow::{WorkflowNet, WorkflowAction, Workflow}, part_possibility::PartPossibility};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Finding {
+    NoStart,
+    Undefined { workflow: String, target: String },
+    // A loop that some parts from in go round forever, as the workflows around it starting from
+    // the first by name. Each distinct loop is reported once.
+    Cycle(Vec<String>),
+    Unreachable(String),
+    // The rule counting from 0, or None for the fallback at the end
+    Shadowed { workflow: String, rule: Option<usize> },
+    Constant { workflow: String, action: WorkflowAction }
+}
+
+impl Finding {
+    // Errors stop parts being evaluated, as they would loop forever or reach a missing workflow
+    pub fn is_error(&self) -> bool {
+        matches!(self, Finding::NoStart | Finding::Undefined { .. } | Finding::Cycle(_))
+    }
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Finding::NoStart => write!(f, "There is no workflow called in"),
+            Finding::Undefined { workflow, target } => write!(f, "Workflow {workflow} sends parts to {target}, which is not defined"),
+            Finding::Cycle(names) => write!(f, "Workflows {} -> {} loop forever", names.join(" -> "), names[0]),
+            Finding::Unreachable(workflow) => write!(f, "Workflow {workflow} is never reached from in"),
+            Finding::Shadowed { workflow, rule: Some(rule) } => write!(f, "Rule {} of workflow {workflow} never matches a part the rules before it let through", rule + 1),
+            Finding::Shadowed { workflow, rule: None } => write!(f, "The rules of workflow {workflow} match every part, so its fallback is never used"),
+            Finding::Constant { workflow, action: WorkflowAction::Accept } => write!(f, "Workflow {workflow} accepts every part"),
+            Finding::Constant { workflow, action: WorkflowAction::Reject } => write!(f, "Workflow {workflow} rejects every part"),
+            Finding::Constant { workflow, action: WorkflowAction::Goto(target) } => write!(f, "Workflow {workflow} sends every part to {target}"),
+        }
+    }
+}
+
+// What each rule, and then the fallback, of a workflow gets out of `possibility`
+fn route<'a>(workflow: &'a Workflow, possibility: &PartPossibility) -> Vec<(Option<usize>, &'a WorkflowAction, Vec<PartPossibility>)> {
+    let mut routes = Vec::new();
+    let mut rest = vec![possibility.clone()];
+    for (i, item) in workflow.items.iter().enumerate() {
+        let mut matched = Vec::new();
+        let mut unmatched = Vec::new();
+        for p in rest.iter() {
+            let (m, r) = p.separate(&item.condition.label, item.condition.op, item.condition.value);
+            matched.extend(m);
+            unmatched.extend(r);
+        }
+        routes.push((Some(i), &item.action, matched));
+        rest = unmatched;
+    }
+    routes.push((None, &workflow.or_else, rest));
+    routes
+}
+
+// The actions a workflow can take on some part within `ranges`, with each rule that can never match
+fn live_actions<'a>(workflow: &'a Workflow, ranges: &PartPossibility) -> (Vec<&'a WorkflowAction>, Vec<Option<usize>>) {
+    let mut actions = Vec::new();
+    let mut shadowed = Vec::new();
+    for (rule, action, possibilities) in route(workflow, ranges) {
+        if possibilities.is_empty() { shadowed.push(rule) } else { actions.push(action) }
+    }
+    (actions, shadowed)
+}
+
+// Follows the parts within `ranges` from in as `sum_all` does, keeping the workflows on the way.
+// Parts that come back to a workflow they have already been through go the same way again, so
+// that loop is endless. Returns every workflow reached and each loop found.
+fn walk<'a>(workflows: &BTreeMap<&'a str, &'a Workflow>, ranges: &PartPossibility) -> (HashSet<&'a str>, BTreeSet<Vec<String>>) {
+    let mut reached = HashSet::new();
+    let mut cycles = BTreeSet::new();
+    let mut states: Vec<(&str, PartPossibility, Vec<&str>)> = match workflows.get_key_value("in") {
+        Some((name, _)) if !ranges.is_empty() => vec![(*name, ranges.clone(), Vec::new())],
+        _ => Vec::new()
+    };
+    while let Some((at, possibility, mut path)) = states.pop() {
+        if let Some(start) = path.iter().position(|p| *p == at) {
+            let mut cycle: Vec<String> = path[start..].iter().map(|s| s.to_string()).collect();
+            let first = (0..cycle.len()).min_by_key(|&i| &cycle[i]).unwrap();
+            cycle.rotate_left(first);
+            cycles.insert(cycle);
+            continue;
+        }
+        reached.insert(at);
+        path.push(at);
+        for (_, action, possibilities) in route(workflows[at], &possibility) {
+            if let WorkflowAction::Goto(target) = action {
+                if let Some((next, _)) = workflows.get_key_value(target.as_str()) {
+                    states.extend(possibilities.into_iter().map(|p| (*next, p, path.clone())));
+                }
+            }
+        }
+    }
+    (reached, cycles)
+}
+
+// Everything wrong with the net that can be seen without any parts, for parts within `ranges`
+pub fn analyse(net: &WorkflowNet, ranges: &PartPossibility) -> Vec<Finding> {
+    let workflows: BTreeMap<&str, &Workflow> = net.workflows().iter().map(|(name, w)| (name.as_str(), w)).collect();
+    let mut findings = Vec::new();
+    if !workflows.contains_key("in") {
+        findings.push(Finding::NoStart);
+    }
+
+    for (name, workflow) in workflows.iter() {
+        for action in workflow.items.iter().map(|item| &item.action).chain([&workflow.or_else]) {
+            match action {
+                WorkflowAction::Goto(target) if !workflows.contains_key(target.as_str()) =>
+                    findings.push(Finding::Undefined { workflow: name.to_string(), target: target.clone() }),
+                _ => (),
+            }
+        }
+    }
+
+    let mut warnings = Vec::new();
+    for (name, workflow) in workflows.iter() {
+        let (actions, shadowed) = live_actions(workflow, ranges);
+        for rule in shadowed {
+            warnings.push(Finding::Shadowed { workflow: name.to_string(), rule });
+        }
+        if let Some(first) = actions.first().filter(|first| actions.iter().all(|a| a == *first)) {
+            warnings.push(Finding::Constant { workflow: name.to_string(), action: (*first).clone() });
+        }
+    }
+
+    let (reached, cycles) = walk(&workflows, ranges);
+    findings.extend(cycles.into_iter().map(Finding::Cycle));
+    findings.extend(workflows.keys().filter(|name| !reached.contains(*name)).map(|name| Finding::Unreachable(name.to_string())));
+    findings.extend(warnings);
+    findings
+}
+
+// Runs the analysis before any parts are evaluated, warning about odd workflows and stopping on broken ones
+pub fn validate(net: &WorkflowNet, ranges: &PartPossibility) {
+    let findings = analyse(net, ranges);
+    for finding in findings.iter().filter(|f| !f.is_error()) {
+        eprintln!("warning: {finding}");
+    }
+    let errors: Vec<_> = findings.iter().filter(|f| f.is_error()).map(|f| f.to_string()).collect();
+    if !errors.is_empty() {
+        panic!("{}", errors.join("\n"));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::{workflow::{WorkflowNet, WorkflowAction}, part_possibility::PartPossibility};
+    use super::{analyse, validate, Finding};
+
+    #[test]
+    fn vscode_help() { }
+
+    fn findings(s: &str) -> Vec<Finding> {
+        let net = WorkflowNet::from_lines(&mut s.lines().map(|l| l.to_string())).unwrap();
+        analyse(&net, &PartPossibility::unbounded(net.categories()))
+    }
+
+    fn constant(workflow: &str, action: WorkflowAction) -> Finding {
+        Finding::Constant { workflow: workflow.to_string(), action }
+    }
+
+    #[test]
+    fn test_example() {
+        assert_eq!(vec![constant("gd", WorkflowAction::Reject), constant("lnx", WorkflowAction::Accept)],
+            findings(include_str!("test_input.txt")));
+    }
+
+    #[test_case("in{x<5:a,R}\na{x<3:in,b}\nb{x>0:a,a}", vec![
+        Finding::Cycle(vec!["a".to_string(), "b".to_string()]),
+        Finding::Cycle(vec!["a".to_string(), "in".to_string()]),
+        constant("b", WorkflowAction::Goto("a".to_string()))
+    ]; "cycles")]
+    #[test_case("in{x<5:a,A}\na{x>10:in,R}", vec![]; "no part loops")]
+    #[test_case("in{x<5:a,R}\na{x<3:b,c}\nb{x<5:c,R}\nc{x<5:a,R}", vec![
+        Finding::Cycle(vec!["a".to_string(), "b".to_string(), "c".to_string()]),
+        Finding::Cycle(vec!["a".to_string(), "c".to_string()])
+    ]; "loops sharing a workflow")]
+    #[test_case("in{x<5:zz,A}", vec![Finding::Undefined { workflow: "in".to_string(), target: "zz".to_string() }]; "undefined")]
+    #[test_case("a{R}", vec![Finding::NoStart, Finding::Unreachable("a".to_string()), constant("a", WorkflowAction::Reject)]; "no start")]
+    #[test_case("in{x<5:R,x>5:A,R}\nlost{x<5:in,R}\nalso{x<5:lost,R}", vec![
+        Finding::Unreachable("also".to_string()),
+        Finding::Unreachable("lost".to_string())
+    ]; "unreachable")]
+    #[test_case("in{x>10:A,x>=20:R,x==10:R,A}", vec![Finding::Shadowed { workflow: "in".to_string(), rule: Some(1) }]; "shadowed")]
+    #[test_case("in{x<=10:A,x>=11:R,A}", vec![Finding::Shadowed { workflow: "in".to_string(), rule: None }]; "fallback")]
+    fn test_findings(s: &str, result: Vec<Finding>) {
+        assert_eq!(result, findings(s));
+    }
+
+    #[test]
+    fn test_ranges() {
+        let net = WorkflowNet::from_lines(&mut "in{x<1:R,A}".lines().map(|l| l.to_string())).unwrap();
+        assert_eq!(vec![
+            Finding::Shadowed { workflow: "in".to_string(), rule: Some(0) },
+            constant("in", WorkflowAction::Accept)
+        ], analyse(&net, &PartPossibility::parse_ranges("x=1..4000").unwrap()));
+    }
+
+    #[test]
+    fn test_validate_terminating() {
+        let net = WorkflowNet::from_lines(&mut "in{x<5:a,A}\na{x>10:in,R}".lines().map(|l| l.to_string())).unwrap();
+        validate(&net, &PartPossibility::unbounded(net.categories()));
+        assert_eq!(Some(6), net.sum_all(&PartPossibility::full(&["x"], 1, 10)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Workflows in -> in loop forever")]
+    fn test_validate_cycle() {
+        let net = WorkflowNet::from_lines(&mut "in{x<5:in,A}".lines().map(|l| l.to_string())).unwrap();
+        validate(&net, &PartPossibility::unbounded(net.categories()));
+    }
+}
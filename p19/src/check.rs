@@ -0,0 +1,21 @@
+use crate::{get_lines::get_lines, workflow::WorkflowNet, part_possibility::PartPossibility, analysis::analyse};
+
+pub fn check_main(args: &Vec<&String>) {
+    if args.len() < 1 {
+        panic!("Must provide the path of the file to open");
+    }
+    let path = args[0];
+    let mut lines = get_lines(path);
+    let workflow = WorkflowNet::from_lines(&mut lines).unwrap_or_else(|err| panic!("{err}"));
+    let ranges = args.get(1)
+        .map(|s| PartPossibility::parse_ranges(s).unwrap_or_else(|err| panic!("{err}")))
+        .unwrap_or_else(|| PartPossibility::unbounded(workflow.categories()));
+    let findings = analyse(&workflow, &ranges);
+    for finding in findings.iter() {
+        let level = if finding.is_error() { "error" } else { "warning" };
+        println!("{level}: {finding}");
+    }
+    if findings.is_empty() {
+        println!("No problems found");
+    }
+}
@@ -1,16 +1,17 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum Label {
-    X, M, A, S
-}
+use std::fmt::Display;
+
+// The name of a part category, such as `x` or `shininess`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Label(String);
 
 impl Label {
-    pub fn from_char(c: char) -> Label {
-        match c {
-            'x' => Label::X,
-            'm' => Label::M,
-            'a' => Label::A,
-            's' => Label::S,
-            _ => panic!()
-        }
+    pub fn new(name: &str) -> Label {
+        Label(name.to_string())
+    }
+}
+
+impl Display for Label {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
     }
-}
\ No newline at end of file
+}
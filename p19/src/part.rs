@@ -1,25 +1,28 @@
 use std::collections::HashMap;
 
-use crate::label::Label;
+use crate::{label::Label, workflow::ParseError};
 
 pub struct Part {
-    items: HashMap<Label, u32>    
+    items: HashMap<Label, u32>
 }
 
 impl Part {
-    pub fn from_str(s: &str) -> Self {
-        let parts = (&s[1..s.len()-1]).split(',');
-        let mut items = HashMap::new();
-        parts.for_each(|part| {
-            let mut s = part.split('=');
-            let name = Label::from_char(s.next().unwrap().chars().next().unwrap());
-            let count: u32 = s.next().unwrap().parse().unwrap();
-            items.insert(name, count);
-        });
-        Self { items }
+    // Reads `{x=787,m=2655}` with any category names
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        let bad = || ParseError::Part(s.to_string());
+        let inner = s.strip_prefix('{').and_then(|s| s.strip_suffix('}')).ok_or_else(bad)?;
+        let items = inner.split(',')
+            .map(|item| {
+                let (name, count) = item.split_once('=').ok_or_else(bad)?;
+                let count: u32 = count.parse().map_err(|_| bad())?;
+                if name.is_empty() { Err(bad()) } else { Ok((Label::new(name), count)) }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { items })
     }
 
-    pub fn get(&self, c: &Label) -> u32 { self.items[c] }
+    // A part without the category has no rating for it, so no condition on it holds
+    pub fn get(&self, c: &Label) -> Option<u32> { self.items.get(c).copied() }
 
     pub fn sum(&self) -> u32 { self.items.values().sum() }
-}
\ No newline at end of file
+}
@@ -1,4 +1,4 @@
-use crate::{get_lines::get_lines, workflow::WorkflowNet, part::Part};
+use crate::{get_lines::get_lines, workflow::WorkflowNet, part::Part, part_possibility::PartPossibility, analysis::validate};
 
 pub fn part1_main(args: &Vec<&String>) {
     if args.len() < 1 {
@@ -6,8 +6,11 @@ pub fn part1_main(args: &Vec<&String>) {
     }
     let path = args[0];
     let mut lines = get_lines(path);
-    let workflow = WorkflowNet::from_lines(&mut lines);
-    let result: u32 = lines.map(|line| workflow.sum_if_accepted(&Part::from_str(&line))).sum();
+    let workflow = WorkflowNet::from_lines(&mut lines).unwrap_or_else(|err| panic!("{err}"));
+    validate(&workflow, &PartPossibility::unbounded(workflow.categories()));
+    let result: u32 = lines
+        .map(|line| workflow.sum_if_accepted(&Part::parse(&line).unwrap_or_else(|err| panic!("{err}"))))
+        .sum();
 
     println!("{result}");
-}
\ No newline at end of file
+}
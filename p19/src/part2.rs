@@ -1,13 +1,17 @@
-use crate::{get_lines::get_lines, workflow::WorkflowNet};
+use crate::{get_lines::get_lines, workflow::WorkflowNet, part_possibility::PartPossibility, analysis::validate};
 
 pub fn part2_main(args: &Vec<&String>) {
     if args.len() < 1 {
         panic!("Must provide the path of the file to open");
     }
     let path = args[0];
+    let ranges = args.get(1)
+        .map(|s| PartPossibility::parse_ranges(s).unwrap_or_else(|err| panic!("{err}")))
+        .unwrap_or_else(|| PartPossibility::full(&["x", "m", "a", "s"], 1, 4000));
     let mut lines = get_lines(path);
-    let workflow = WorkflowNet::from_lines(&mut lines);
-    let result = workflow.sum_all();
+    let workflow = WorkflowNet::from_lines(&mut lines).unwrap_or_else(|err| panic!("{err}"));
+    validate(&workflow, &ranges);
+    let result = workflow.sum_all(&ranges).unwrap_or_else(|| panic!("Too many parts to count"));
 
     println!("{result}");
-}
\ No newline at end of file
+}
@@ -1,88 +1,127 @@
-use std::ops::Range;
+use std::{collections::BTreeMap, ops::Range};
 
-use crate::label::Label;
+use crate::{label::Label, workflow::{Operator, ParseError}};
 
+// Every part with a rating in each category's range
 #[derive(Debug, Clone)]
 pub struct PartPossibility {
-    x: Range<u32>,
-    m: Range<u32>,
-    a: Range<u32>,
-    s: Range<u32>   
+    ranges: BTreeMap<Label, Range<u32>>
 }
 
 impl PartPossibility {
-    pub fn full(min: u32, max: u32) -> Self {
-        Self { x: min..(max+1), m: min..(max+1), a: min..(max+1), s: min..(max+1) }
+    pub fn new(ranges: BTreeMap<Label, Range<u32>>) -> Self {
+        Self { ranges }
     }
 
-    pub fn empty() -> Self {
-        Self { x: 0..0, m: 0..0, a: 0..0, s: 0..0 }
+    // Every category given the same inclusive range, as in the puzzle
+    pub fn full(labels: &[&str], min: u32, max: u32) -> Self {
+        Self::new(labels.iter().map(|l| (Label::new(l), min..(max+1))).collect())
     }
 
-    pub fn get(&self, label: Label) -> &Range<u32> {
-        match label {
-            Label::X => &self.x,
-            Label::M => &self.m,
-            Label::A => &self.a,
-            Label::S => &self.s,
-        }
+    // Every rating a part could have in each category, short of u32::MAX itself
+    pub fn unbounded(labels: impl IntoIterator<Item = Label>) -> Self {
+        Self::new(labels.into_iter().map(|l| (l, 0..u32::MAX)).collect())
     }
 
-    pub fn get_mut(&mut self, label: Label) -> &mut Range<u32> {
-        match label {
-            Label::X => &mut self.x,
-            Label::M => &mut self.m,
-            Label::A => &mut self.a,
-            Label::S => &mut self.s,
+    // Reads inclusive ranges like `x=1..4000,m=1..4000`, each category once and with min <= max
+    pub fn parse_ranges(s: &str) -> Result<Self, ParseError> {
+        let bad = || ParseError::Ranges(s.to_string());
+        let mut ranges = BTreeMap::new();
+        for item in s.split(',') {
+            let (name, range) = item.split_once('=').ok_or_else(bad)?;
+            let (min, max) = range.split_once("..").ok_or_else(bad)?;
+            let min: u32 = min.parse().map_err(|_| bad())?;
+            let max: u32 = max.parse().map_err(|_| bad())?;
+            if min > max || ranges.insert(Label::new(name), min..max.saturating_add(1)).is_some() {
+                return Err(bad());
+            }
         }
+        Ok(Self::new(ranges))
     }
 
-    pub fn replacing(&self, label: Label, range: Range<u32>) -> PartPossibility {
-        match label {
-            Label::X => Self { x: range, ..self.clone() },
-            Label::M => Self { m: range, ..self.clone() },
-            Label::A => Self { a: range, ..self.clone() },
-            Label::S => Self { s: range, ..self.clone() },
-        }
+    fn replacing(&self, label: &Label, range: Range<u32>) -> PartPossibility {
+        let mut ranges = self.ranges.clone();
+        ranges.insert(label.clone(), range);
+        Self { ranges }
     }
 
-    pub fn separate_lt(&mut self, label: Label, part: u32) -> PartPossibility {
-        let range = self.get_mut(label);
-        if part < range.start {
-            Self::empty()
-        }
-        else if part >= range.end {
-            let full_range = range.clone();
-            *range = 0..0;
-            self.replacing(label, full_range)
-        }
-        else {
-            let start = range.start;
-            *range = part..range.end;
-            let rest = start..part;
-            self.replacing(label, rest)
-        }
+    // Splits into the parts that meet `label op value` and those that do not. Each side can be
+    // more than one possibility, since == and != cut a range in two. A category that is not
+    // in the possibility never meets a condition.
+    pub fn separate(&self, label: &Label, op: Operator, value: u32) -> (Vec<PartPossibility>, Vec<PartPossibility>) {
+        let range = match self.ranges.get(label) {
+            Some(range) => range.clone(),
+            None => return (Vec::new(), vec![self.clone()]),
+        };
+        let below = |k: u32| range.start..k.min(range.end);
+        let from = |k: u32| k.max(range.start)..range.end;
+        let next = value.saturating_add(1);
+        let equal = from(value).start..below(next).end;
+        let (matched, rest) = match op {
+            Operator::Lt => (vec![below(value)], vec![from(value)]),
+            Operator::Le => (vec![below(next)], vec![from(next)]),
+            Operator::Gt => (vec![from(next)], vec![below(next)]),
+            Operator::Ge => (vec![from(value)], vec![below(value)]),
+            Operator::Eq => (vec![equal], vec![below(value), from(next)]),
+            Operator::Ne => (vec![below(value), from(next)], vec![equal]),
+        };
+        let to_possibilities = |ranges: Vec<Range<u32>>| ranges.into_iter()
+            .filter(|r| !r.is_empty())
+            .map(|r| self.replacing(label, r))
+            .collect();
+        (to_possibilities(matched), to_possibilities(rest))
     }
 
-    pub fn separate_gt(&mut self, label: Label, part: u32) -> PartPossibility {
-        let range = self.get_mut(label);
-        if part + 1 >= range.end {
-            Self::empty()
-        }
-        else if part < range.start {
-            let full_range = range.clone();
-            *range = 0..0;
-            self.replacing(label, full_range)
-        }
-        else {
-            let end = range.end;
-            *range = range.start..(part+1);
-            let rest = (part+1)..end;
-            self.replacing(label, rest)
-        }
+    pub fn is_empty(&self) -> bool {
+        self.ranges.values().any(|r| r.is_empty())
     }
 
-    pub fn size(&self) -> usize {
-        self.x.len() * self.m.len() * self.a.len() * self.s.len()
+    // How many parts there are, or None if that does not fit in a u128
+    pub fn size(&self) -> Option<u128> {
+        self.ranges.values().try_fold(1u128, |size, r| size.checked_mul(r.len() as u128))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::{label::Label, workflow::Operator};
+    use super::PartPossibility;
+
+    #[test]
+    fn vscode_help() { }
+
+    #[test_case(Operator::Lt, 5, 4, 6; "less than")]
+    #[test_case(Operator::Le, 5, 5, 5; "at most")]
+    #[test_case(Operator::Gt, 5, 5, 5; "greater than")]
+    #[test_case(Operator::Ge, 5, 6, 4; "at least")]
+    #[test_case(Operator::Eq, 5, 1, 9; "equal")]
+    #[test_case(Operator::Ne, 5, 9, 1; "not equal")]
+    #[test_case(Operator::Lt, 0, 0, 10; "below the range")]
+    #[test_case(Operator::Gt, 10, 0, 10; "above the range")]
+    #[test_case(Operator::Eq, 11, 0, 10; "equal outside")]
+    fn test_separate(op: Operator, value: u32, matched: usize, rest: usize) {
+        let possibility = PartPossibility::full(&["x", "m"], 1, 10);
+        let (m, r) = possibility.separate(&Label::new("x"), op, value);
+        assert_eq!(matched as u128 * 10, m.iter().map(|p| p.size().unwrap()).sum::<u128>());
+        assert_eq!(rest as u128 * 10, r.iter().map(|p| p.size().unwrap()).sum::<u128>());
+    }
+
+    #[test]
+    fn test_unknown_category() {
+        let possibility = PartPossibility::full(&["x"], 1, 10);
+        let (m, r) = possibility.separate(&Label::new("y"), Operator::Lt, 5);
+        assert_eq!(0, m.len());
+        assert_eq!(Some(10), r[0].size());
+    }
+
+    #[test]
+    fn test_parse_ranges() {
+        assert_eq!(Some(4000 * 10), PartPossibility::parse_ranges("x=1..4000,y=0..9").unwrap().size());
+        assert_eq!(Some(1), PartPossibility::parse_ranges("x=7..7").unwrap().size());
+        assert!(PartPossibility::parse_ranges("x=1-4000").is_err());
+        assert!(PartPossibility::parse_ranges("x=1..4000,x=1..10").is_err());
+        assert!(PartPossibility::parse_ranges("x=10..1").is_err());
+    }
+}
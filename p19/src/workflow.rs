@@ -1,8 +1,27 @@
-use std::collections::HashMap;
+use std::{collections::{HashMap, BTreeSet}, fmt::Display};
 
 use crate::{part::Part, label::Label, part_possibility::PartPossibility};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    Workflow(String),
+    Condition(String),
+    Part(String),
+    Ranges(String)
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Workflow(s) => write!(f, "Could not read workflow {s}"),
+            ParseError::Condition(s) => write!(f, "Could not read condition {s}"),
+            ParseError::Part(s) => write!(f, "Could not read part {s}"),
+            ParseError::Ranges(s) => write!(f, "Could not read ranges {s}, use x=1..4000,m=1..4000"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum WorkflowAction {
     Accept,
     Reject,
@@ -19,45 +38,77 @@ impl WorkflowAction {
     }
 }
 
+impl Display for WorkflowAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkflowAction::Accept => write!(f, "A"),
+            WorkflowAction::Reject => write!(f, "R"),
+            WorkflowAction::Goto(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Lt, Gt, Le, Ge, Eq, Ne
+}
+
+impl Operator {
+    // Two character operators first, so `<=` is not read as `<`
+    const ALL: [(&'static str, Operator); 6] = [
+        ("<=", Operator::Le), (">=", Operator::Ge), ("==", Operator::Eq), ("!=", Operator::Ne),
+        ("<", Operator::Lt), (">", Operator::Gt)
+    ];
+
+    pub fn apply(&self, a: u32, b: u32) -> bool {
+        match self {
+            Operator::Lt => a < b,
+            Operator::Gt => a > b,
+            Operator::Le => a <= b,
+            Operator::Ge => a >= b,
+            Operator::Eq => a == b,
+            Operator::Ne => a != b,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
-pub enum WorkflowCondition {
-    LessThan(Label, u32),
-    GreaterThan(Label, u32)
+pub struct WorkflowCondition {
+    pub label: Label,
+    pub op: Operator,
+    pub value: u32
 }
 
 impl WorkflowCondition {
-    fn from_str(s: &str) -> WorkflowCondition {
-        let mut parts = s.split(&['<', '>']);
-        let name = Label::from_char(parts.next().unwrap().chars().next().unwrap());
-        let num: u32 = parts.next().unwrap().parse().unwrap();
-        if s.contains("<") {
-            WorkflowCondition::LessThan(name, num)
-        }
-        else {
-            WorkflowCondition::GreaterThan(name, num)
+    fn from_str(s: &str) -> Result<WorkflowCondition, ParseError> {
+        let bad = || ParseError::Condition(s.to_string());
+        let start = s.find(['<', '>', '=', '!']).ok_or_else(bad)?;
+        let (name, rest) = s.split_at(start);
+        let (symbol, op) = Operator::ALL.iter().find(|(symbol, _)| rest.starts_with(symbol)).ok_or_else(bad)?;
+        let value = rest[symbol.len()..].parse().map_err(|_| bad())?;
+        if name.is_empty() {
+            return Err(bad());
         }
+        Ok(WorkflowCondition { label: Label::new(name), op: *op, value })
     }
 
     fn check(&self, part: &Part) -> bool {
-        match self {
-            WorkflowCondition::LessThan(c, n) => part.get(c) < *n,
-            WorkflowCondition::GreaterThan(c, n) => part.get(c) > *n,
-        }
+        part.get(&self.label).is_some_and(|n| self.op.apply(n, self.value))
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct WorkflowItem {
-    action: WorkflowAction,
-    condition: WorkflowCondition
+    pub action: WorkflowAction,
+    pub condition: WorkflowCondition
 }
 
 impl WorkflowItem {
-    fn from_str(s: &str) -> WorkflowItem {
-        let mut parts = s.split(':');
-        let condition = WorkflowCondition::from_str(parts.next().unwrap());
-        let action = WorkflowAction::from_str(parts.next().unwrap());
-        Self { condition, action }
+    fn from_str(s: &str) -> Result<WorkflowItem, ParseError> {
+        let (condition, action) = s.split_once(':').ok_or_else(|| ParseError::Condition(s.to_string()))?;
+        let condition = WorkflowCondition::from_str(condition)?;
+        let action = WorkflowAction::from_str(action);
+        Ok(Self { condition, action })
     }
 
     fn check(&self, part: &Part) -> Option<&WorkflowAction> {
@@ -72,42 +123,49 @@ impl WorkflowItem {
 
 #[derive(Debug, Clone)]
 pub struct Workflow {
-    items: Vec<WorkflowItem>,
-    or_else: WorkflowAction
+    pub items: Vec<WorkflowItem>,
+    pub or_else: WorkflowAction
 }
 
 impl Workflow {
-    fn from_str(s: &str) -> Workflow {
+    fn from_str(s: &str) -> Result<Workflow, ParseError> {
         let parts: Vec<_> = s.split(',').collect();
-        let items = parts.iter().take(parts.len() - 1).map(|s| WorkflowItem::from_str(s)).collect();
+        let items = parts.iter().take(parts.len() - 1).map(|s| WorkflowItem::from_str(s)).collect::<Result<_, _>>()?;
         let or_else = WorkflowAction::from_str(parts.last().unwrap());
-        Self { items, or_else }
+        Ok(Self { items, or_else })
     }
 
     fn check(&self, part: &Part) -> &WorkflowAction {
         self.items.iter().find_map(|item| item.check(part)).unwrap_or(&self.or_else)
     }
 
-    fn split(&self, possibility: PartPossibility, states: &mut Vec<(String, PartPossibility)>) -> usize {
-        let mut rest = possibility;
-        let mut sum = 0;
-        self.items.iter().for_each(|item| {
-            let matched = match item.condition {
-                WorkflowCondition::LessThan(label, part) => rest.separate_lt(label, part),
-                WorkflowCondition::GreaterThan(label, part) => rest.separate_gt(label, part),
-            };
-            match &item.action {
-                WorkflowAction::Accept => sum += matched.size(),
-                WorkflowAction::Reject => (),
-                WorkflowAction::Goto(label) => if matched.size() > 0 { states.push((label.clone(), matched)) },
+    // How many parts are accepted straight away, or None if that does not fit in a u128
+    fn send(action: &WorkflowAction, possibilities: Vec<PartPossibility>, states: &mut Vec<(String, PartPossibility)>) -> Option<u128> {
+        match action {
+            WorkflowAction::Accept => possibilities.iter().try_fold(0u128, |sum, p| sum.checked_add(p.size()?)),
+            WorkflowAction::Reject => Some(0),
+            WorkflowAction::Goto(label) => {
+                states.extend(possibilities.into_iter().filter(|p| !p.is_empty()).map(|p| (label.clone(), p)));
+                Some(0)
+            },
+        }
+    }
+
+    fn split(&self, possibility: PartPossibility, states: &mut Vec<(String, PartPossibility)>) -> Option<u128> {
+        let mut rest = vec![possibility];
+        let mut sum = 0u128;
+        for item in self.items.iter() {
+            let mut matched = Vec::new();
+            let mut unmatched = Vec::new();
+            for p in rest.iter() {
+                let (m, r) = p.separate(&item.condition.label, item.condition.op, item.condition.value);
+                matched.extend(m);
+                unmatched.extend(r);
             }
-        });
-        match &self.or_else {
-            WorkflowAction::Accept => sum += rest.size(),
-            WorkflowAction::Reject => (),
-            WorkflowAction::Goto(label) => if rest.size() > 0 { states.push((label.clone(), rest)) },
+            sum = sum.checked_add(Self::send(&item.action, matched, states)?)?;
+            rest = unmatched;
         }
-        sum
+        sum.checked_add(Self::send(&self.or_else, rest, states)?)
     }
 }
 
@@ -123,21 +181,30 @@ pub enum WorkflowResult {
 }
 
 impl WorkflowNet {
-    pub fn from_lines(lines: &mut impl Iterator<Item = String>) -> Self {
+    pub fn from_lines(lines: &mut impl Iterator<Item = String>) -> Result<Self, ParseError> {
         let mut workflows = HashMap::new();
         while let Some(line) = lines.next() {
             if line == "" { break }
 
-            let mut parts = line.split('{');
-            let name = parts.next().unwrap().to_string();
-            let workflow_str = parts.next().unwrap();
-            let workflow = Workflow::from_str(&workflow_str[0..workflow_str.len()-1]);
+            let bad = || ParseError::Workflow(line.clone());
+            let (name, workflow_str) = line.split_once('{').ok_or_else(bad)?;
+            let workflow = Workflow::from_str(workflow_str.strip_suffix('}').ok_or_else(bad)?)?;
             
-            workflows.insert(name, workflow);
+            workflows.insert(name.to_string(), workflow);
         }
-        Self { workflows }
+        Ok(Self { workflows })
+    }
+
+    pub fn workflows(&self) -> &HashMap<String, Workflow> { &self.workflows }
+
+    // Every category named in a condition
+    pub fn categories(&self) -> BTreeSet<Label> {
+        self.workflows.values()
+            .flat_map(|w| w.items.iter().map(|item| item.condition.label.clone()))
+            .collect()
     }
 
+    // Only safe on a net without cycles or undefined workflows, see `analysis::validate`
     fn check(&self, part: &Part) -> WorkflowResult {
         let mut at = "in";
         loop {
@@ -156,12 +223,70 @@ impl WorkflowNet {
         }
     }
 
-    pub fn sum_all(&self) -> usize {
-        let mut states = vec![("in".to_string(), PartPossibility::full(1, 4000))];
-        let mut sum = 0;
+    // How many parts within `ranges` are accepted, or None if there are more than fit in a u128
+    pub fn sum_all(&self, ranges: &PartPossibility) -> Option<u128> {
+        let mut states = vec![("in".to_string(), ranges.clone())];
+        let mut sum = 0u128;
         while let Some((at, possibility)) = states.pop() {
-            sum += self.workflows[&at].split(possibility, &mut states);
+            sum = sum.checked_add(self.workflows[&at].split(possibility, &mut states)?)?;
         }
-        sum
+        Some(sum)
+    }
+}
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::{part::Part, part_possibility::PartPossibility};
+    use super::{WorkflowNet, ParseError};
+
+    #[test]
+    fn vscode_help() { }
+
+    fn net(s: &str) -> WorkflowNet {
+        WorkflowNet::from_lines(&mut s.lines().map(|l| l.to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_example() {
+        let s = include_str!("test_input.txt");
+        let workflow = net(s);
+        let parts: u32 = s.lines().skip_while(|l| !l.is_empty()).skip(1)
+            .map(|l| workflow.sum_if_accepted(&Part::parse(l).unwrap()))
+            .sum();
+        assert_eq!(19114, parts);
+        assert_eq!(Some(167409079868000), workflow.sum_all(&PartPossibility::full(&["x", "m", "a", "s"], 1, 4000)));
+    }
+
+    #[test_case("in{shine<=5:A,R}", "{shine=5}", 5; "at most")]
+    #[test_case("in{shine>=5:A,R}", "{shine=4}", 0; "at least")]
+    #[test_case("in{shine==5:R,A}", "{shine=5,x=1}", 0; "equal")]
+    #[test_case("in{shine!=5:A,R}", "{shine=6}", 6; "not equal")]
+    #[test_case("in{shine<5:R,A}", "{x=1}", 1; "missing category")]
+    fn test_operators(workflows: &str, part: &str, result: u32) {
+        assert_eq!(result, net(workflows).sum_if_accepted(&Part::parse(part).unwrap()));
+    }
+
+    #[test_case("in{a<=3:A,a!=7:R,A}", "a=1..10", 3 + 1; "mixed")]
+    #[test_case("in{a==3:b,R}\nb{a>=3:A,R}", "a=1..10,z=1..2", 2; "equal then at least")]
+    #[test_case("in{a!=3:R,b<2:A,R}", "a=0..9,b=0..3", 2; "not equal")]
+    fn test_sum_all(workflows: &str, ranges: &str, result: u128) {
+        assert_eq!(Some(result), net(workflows).sum_all(&PartPossibility::parse_ranges(ranges).unwrap()));
+    }
+
+    #[test]
+    fn test_sum_all_large() {
+        let ranges = PartPossibility::parse_ranges("x=0..4000000000,m=0..4000000000,a=0..4000000000").unwrap();
+        assert_eq!(Some(4000000001u128.pow(3)), net("in{A}").sum_all(&ranges));
+        let ranges = PartPossibility::parse_ranges("x=0..4294967294,m=0..4294967294,a=0..4294967294,s=0..4294967294,t=0..1").unwrap();
+        assert_eq!(None, net("in{A}").sum_all(&ranges));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |s: &str| WorkflowNet::from_lines(&mut s.lines().map(|l| l.to_string())).err();
+        assert_eq!(Some(ParseError::Condition("a=<3".to_string())), parse("in{a=<3:A,R}"));
+        assert_eq!(Some(ParseError::Workflow("in{a<3:A,R".to_string())), parse("in{a<3:A,R"));
+        assert!(Part::parse("{x=}").is_err());
+    }
+}
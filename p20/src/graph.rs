@@ -0,0 +1,12 @@
+use crate::{get_lines::get_lines, modules::ModuleNet, graph_export::GraphFormat};
+
+pub fn graph_main(args: &Vec<&String>) {
+    if args.len() < 2 {
+        panic!("Must provide the output format (dot or mermaid) and the path of the file to open");
+    }
+    let format = GraphFormat::parse(args[0]).unwrap_or_else(|| panic!("Unknown graph format {}", args[0]));
+    let path = args[1];
+    let mods = ModuleNet::from_lines(get_lines(path));
+
+    print!("{}", mods.to_graph().write(format));
+}
@@ -1,5 +1,7 @@
 use std::{collections::{HashMap, VecDeque, HashSet}, fmt::Display};
 
+use crate::graph_export::{Graph, NodeShape};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Pulse {
     Lo, Hi
@@ -142,4 +144,36 @@ impl ModuleNet {
             }
         }
     }
-}
\ No newline at end of file
+
+    pub fn to_graph(&self) -> Graph {
+        let mut graph = Graph::new();
+        graph.add_node("broadcaster", NodeShape::Circle);
+        let mut names: Vec<_> = self.module_outputs.keys().collect();
+        names.sort();
+        for name in names.iter() {
+            let shape = match self.state[*name] {
+                ModuleState::FlipFlop(_) => NodeShape::Box,
+                ModuleState::Conjunction(_) => NodeShape::Diamond,
+            };
+            graph.add_node(name, shape);
+        }
+        // Outputs like rx that never send anything have no state of their own
+        let mut sinks: Vec<_> = self.module_outputs.values().flatten()
+            .chain(self.broadcast.iter())
+            .filter(|out| !self.state.contains_key(*out))
+            .collect::<HashSet<_>>().into_iter().collect();
+        sinks.sort();
+        for sink in sinks {
+            graph.add_node(sink, NodeShape::Ellipse);
+        }
+        for to in self.broadcast.iter() {
+            graph.add_edge("broadcaster", to, None);
+        }
+        for name in names {
+            for to in self.module_outputs[name].iter() {
+                graph.add_edge(name, to, None);
+            }
+        }
+        graph
+    }
+}
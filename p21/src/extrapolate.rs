@@ -0,0 +1,24 @@
+use crate::{get_lines::get_lines, plot::Plot};
+
+pub fn extrapolate_main(args: &Vec<&String>) {
+    if args.len() < 2 {
+        panic!("Must provide the path of the file to open and the desired step count");
+    }
+    let path = args[0];
+    let steps: u32 = args[1].parse().unwrap();
+    // Small gardens take a couple of periods before the count grows quadratically
+    let warmup: u32 = args.get(2).map(|w| w.parse().unwrap()).unwrap_or(2);
+    let plot = Plot::from_lines(get_lines(path));
+    // One sample more than a quadratic needs, so that the fit can be checked
+    let (poly, extrapolated) = plot.get_steps_extrapolated(steps, warmup, 4);
+    if !poly.is_low_degree() {
+        eprintln!("warning: the samples only fit a polynomial of degree {}, try a longer warmup", poly.degree());
+    }
+    let extrapolated = extrapolated.unwrap_or_else(|| panic!("Extrapolation is not a whole number or overflowed, try a longer warmup"));
+    let repeating = plot.get_steps_repeating(steps);
+    if extrapolated != repeating {
+        eprintln!("warning: extrapolated {extrapolated} but get_steps_repeating gave {repeating}");
+    }
+
+    println!("{extrapolated}");
+}
@@ -1,6 +1,6 @@
 use std::collections::{HashMap, VecDeque};
 
-use crate::{vector2::Vector2, direction::Direction, argm::Argm};
+use crate::{vector2::Vector2, direction::Direction, argm::Argm, poly::Polynomial};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Tile {
@@ -278,6 +278,43 @@ impl Plot {
         center + axis_n + axis_e + axis_s + axis_w + corner_ne + corner_nw + corner_se + corner_sw
     }
 
+    // Plain search over the infinitely repeating garden, only practical for small step counts
+    pub fn count_steps_infinite(&self, steps: u32) -> u64 {
+        let start = Vector2::new(self.start.x as i64, self.start.y as i64);
+        let mut seen = HashMap::new();
+        let mut to_explore = VecDeque::new();
+        to_explore.push_back((start, 0u32));
+        seen.insert(start, 0u32);
+        while let Some((at, step_count)) = to_explore.pop_front() {
+            if step_count == steps { continue }
+            for dir in Direction::all() {
+                let next = dir.moved(&at);
+                let x = next.x.rem_euclid(self.width() as i64) as usize;
+                let y = next.y.rem_euclid(self.height() as i64) as usize;
+                if self.data[y][x] == Tile::Garden && !seen.contains_key(&next) {
+                    seen.insert(next, step_count + 1);
+                    to_explore.push_back((next, step_count + 1));
+                }
+            }
+        }
+        seen.values().filter(|v| *v % 2 == steps % 2).count() as u64
+    }
+
+    // Counts exactly at steps % p + k * p for a few k after `warmup` periods, fits a polynomial in k
+    // and evaluates it at steps / p. The period is two widths so that the parity of the tiles lines up,
+    // and the fit should be quadratic once the frontier crosses whole tiles.
+    pub fn get_steps_extrapolated(&self, steps: u32, warmup: u32, samples: u32) -> (Polynomial, Option<u64>) {
+        let period = 2 * self.width() as u32;
+        let base = steps % period;
+        let counts: Vec<i128> = (warmup..warmup + samples)
+            .map(|k| self.count_steps_infinite(base + k * period) as i128)
+            .collect();
+        let poly = Polynomial::from_sequence(&counts).unwrap_or_else(|| panic!("Overflow fitting a polynomial to the step counts"));
+        let k = (steps / period) as i128 - warmup as i128;
+        let value = poly.eval(k).and_then(|v| v.to_integer()).and_then(|v| v.try_into().ok());
+        (poly, value)
+    }
+
     pub fn get_steps_repeating_brute(&self, steps: u32) {
         #[derive(Hash, PartialEq, Eq, Clone, Copy, Debug)]
         struct LayerIndex {
@@ -336,3 +373,45 @@ impl Plot {
         println!("{total}");
     }
 }
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::Plot;
+
+    #[test]
+    fn vscode_help() { }
+
+    const EXAMPLE: &str = "...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........";
+
+    #[test_case(6, 16)]
+    #[test_case(10, 50)]
+    #[test_case(50, 1594)]
+    #[test_case(100, 6536)]
+    fn test_count_steps_infinite(steps: u32, result: u64) {
+        let plot = Plot::from_lines(EXAMPLE.lines().map(|l| l.to_string()));
+        assert_eq!(result, plot.count_steps_infinite(steps));
+    }
+
+    #[test_case(500, 167004)]
+    #[test_case(1000, 668697)]
+    #[test_case(5000, 16733044)]
+    fn test_extrapolated(steps: u32, result: u64) {
+        let plot = Plot::from_lines(EXAMPLE.lines().map(|l| l.to_string()));
+        let (poly, value) = plot.get_steps_extrapolated(steps, 2, 4);
+        assert!(poly.is_low_degree());
+        assert_eq!(Some(result), value);
+        assert_eq!(result, plot.get_steps_repeating(steps));
+    }
+}
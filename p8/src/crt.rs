@@ -0,0 +1,60 @@
+use crate::lcm::lcm;
+
+// Returns (g, x, y) such that a * x + b * y = g = gcd(a, b)
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    }
+    else {
+        let (g, x, y) = extended_gcd(b, a % b);
+        (g, y, x - (a / b) * y)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrtError {
+    Conflict,
+    Overflow
+}
+
+// Solves x = r1 (mod m1), x = r2 (mod m2) for positive moduli that need not be coprime.
+// Returns (r, lcm(m1, m2)) with 0 <= r < lcm(m1, m2), Conflict if the congruences have no
+// common solution, or Overflow if the lcm or the working does not fit in an i128.
+pub fn crt_pair(r1: i128, m1: i128, r2: i128, m2: i128) -> Result<(i128, i128), CrtError> {
+    let (g, p, _) = extended_gcd(m1, m2);
+    let diff = r2 - r1;
+    if diff % g != 0 {
+        return Err(CrtError::Conflict);
+    }
+    let m = lcm(m1, m2).ok_or(CrtError::Overflow)?;
+    let step = m2 / g;
+    let k = (diff / g).rem_euclid(step)
+        .checked_mul(p.rem_euclid(step))
+        .ok_or(CrtError::Overflow)?
+        .rem_euclid(step);
+    // k < m2 / g, so k * m1 < m and cannot overflow
+    let r = (r1 + k * m1).rem_euclid(m);
+    Ok((r, m))
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::{crt_pair, CrtError};
+
+    #[test]
+    fn vscode_help() { }
+
+    #[test_case((0, 1), (2, 3), Ok((2, 3)); "trivial")]
+    #[test_case((2, 3), (3, 5), Ok((8, 15)); "coprime")]
+    #[test_case((8, 15), (2, 7), Ok((23, 105)); "coprime again")]
+    #[test_case((2, 4), (4, 6), Ok((10, 12)); "shared factor")]
+    #[test_case((1, 4), (2, 6), Err(CrtError::Conflict); "conflict")]
+    #[test_case((3, 6), (3, 6), Ok((3, 6)); "same")]
+    #[test_case((1, 1 << 64), (2, (1 << 64) + 1), Err(CrtError::Overflow); "lcm too big")]
+    #[test_case((1, 1 << 62), (2, (1 << 64) + 1), Ok((85070591730234615852008593802659889153, (1 << 126) + (1 << 62))); "large")]
+    fn test_crt_pair(first: (i128, i128), second: (i128, i128), result: Result<(i128, i128), CrtError>) {
+        assert_eq!(result, crt_pair(first.0, first.1, second.0, second.1));
+    }
+}
@@ -0,0 +1,203 @@
+use std::fmt::Display;
+
+use crate::{map::Direction, network::Network, crt::{crt_pair, CrtError}};
+
+// The walk of one ghost, split into a prefix and the (node, instruction index) cycle it falls into
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GhostCycle {
+    pub prefix: u64,
+    pub period: u64,
+    // Steps before the cycle starts at which the ghost is on an end node
+    pub prefix_hits: Vec<u64>,
+    // Steps in [prefix, prefix + period) at which the ghost is on an end node
+    pub cycle_hits: Vec<u64>
+}
+
+impl GhostCycle {
+    pub fn analyse(network: &Network, directions: &[Direction], start: u32, is_end: impl Fn(u32) -> bool) -> GhostCycle {
+        // First step at which each (node, instruction index) state was seen
+        let mut seen = vec![u64::MAX; network.node_count() * directions.len()];
+        let mut hits = Vec::new();
+        let mut at = start;
+        let mut step = 0u64;
+        let mut index = 0usize;
+        loop {
+            let state = at as usize * directions.len() + index;
+            let first = seen[state];
+            if first != u64::MAX {
+                let (prefix_hits, cycle_hits) = hits.into_iter().partition(|&t| t < first);
+                return GhostCycle { prefix: first, period: step - first, prefix_hits, cycle_hits };
+            }
+            if is_end(at) {
+                hits.push(step);
+            }
+            seen[state] = step;
+            at = network.go(at, directions[index]);
+            index = (index + 1) % directions.len();
+            step += 1;
+        }
+    }
+
+    pub fn is_hit(&self, step: u64) -> bool {
+        if step < self.prefix {
+            self.prefix_hits.contains(&step)
+        }
+        else {
+            let offset = (step - self.prefix) % self.period + self.prefix;
+            self.cycle_hits.contains(&offset)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncError {
+    NoGhosts,
+    NeverSynchronised,
+    Overflow
+}
+
+impl Display for SyncError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SyncError::NoGhosts => write!(f, "There are no ghosts to synchronise"),
+            SyncError::NeverSynchronised => write!(f, "The ghosts are never all on end nodes at the same time"),
+            SyncError::Overflow => write!(f, "The ghosts' cycles are too long to synchronise without overflowing"),
+        }
+    }
+}
+
+// Finds the first step at which every ghost is on an end node at once
+pub fn synchronise(cycles: &[GhostCycle]) -> Result<u64, SyncError> {
+    if cycles.is_empty() {
+        return Err(SyncError::NoGhosts);
+    }
+
+    // Any step inside some ghost's prefix comes before every step that relies on all cycles
+    let in_prefix = cycles.iter()
+        .flat_map(|c| c.prefix_hits.iter())
+        .filter(|&&t| cycles.iter().all(|c| c.is_hit(t)))
+        .min();
+    if let Some(&t) = in_prefix {
+        return Ok(t);
+    }
+
+    // Merge the ghosts one at a time, keeping every residue that lines up all the ghosts so far.
+    // The residues all share the same modulus, so there are never more of them than that.
+    let mut solutions = vec![(0i128, 1i128)];
+    for cycle in cycles {
+        let mut merged = Vec::new();
+        for &(r, m) in solutions.iter() {
+            for &hit in cycle.cycle_hits.iter() {
+                match crt_pair(r, m, (hit % cycle.period) as i128, cycle.period as i128) {
+                    Ok(solution) => merged.push(solution),
+                    Err(CrtError::Conflict) => (),
+                    Err(CrtError::Overflow) => return Err(SyncError::Overflow)
+                }
+            }
+        }
+        merged.sort_unstable();
+        merged.dedup();
+        solutions = merged;
+    }
+
+    let start = cycles.iter().map(|c| c.prefix).max().unwrap() as i128;
+    // Anything that saturates is far too big for a u64 anyway
+    let first = solutions.iter()
+        .map(|&(r, m)| if r >= start { r } else { r.saturating_add(((start - r - 1) / m + 1).saturating_mul(m)) })
+        .min()
+        .ok_or(SyncError::NeverSynchronised)?;
+    u64::try_from(first).map_err(|_| SyncError::Overflow)
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::{map::{Direction, parse_map}, network::Network};
+    use super::{GhostCycle, synchronise, SyncError};
+
+    #[test]
+    fn vscode_help() { }
+
+    fn sync_str(s: &str) -> Result<u64, SyncError> {
+        let mut lines = s.lines().map(|l| l.to_string());
+        let directions = Direction::from_str(lines.next().unwrap().as_str());
+        _ = lines.next();
+        let network = Network::from_map(&parse_map(&mut lines));
+        let cycles: Vec<_> = network.nodes()
+            .filter(|&n| network.name(n).ends_with('A'))
+            .map(|s| GhostCycle::analyse(&network, &directions, s, |n| network.name(n).ends_with('Z')))
+            .collect();
+        synchronise(&cycles)
+    }
+
+    #[test_case("LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)", Ok(6); "example")]
+    #[test_case("L
+
+11A = (11Z, 11Z)
+11Z = (11B, 11B)
+11B = (11B, 11B)
+22A = (22B, 22B)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22Z, 22Z)", Err(SyncError::NeverSynchronised); "prefix only")]
+    #[test_case("L
+
+11A = (11Z, 11Z)
+11Z = (11B, 11B)
+11B = (11Z, 11Z)
+22A = (22B, 22B)
+22B = (22Z, 22Z)
+22Z = (22B, 22B)", Err(SyncError::NeverSynchronised); "parity")]
+    #[test_case("L
+
+11A = (11B, 11B)
+11B = (11Z, 11Z)
+11Z = (11C, 11C)
+11C = (11B, 11B)
+22A = (22B, 22B)
+22B = (22C, 22C)
+22C = (22D, 22D)
+22D = (22E, 22E)
+22E = (22Z, 22Z)
+22Z = (22D, 22D)", Ok(5); "offset cycles")]
+    #[test_case("L
+
+11A = (11Z, 11Z)
+11Z = (11B, 11B)
+11B = (11B, 11B)
+22A = (22Z, 22Z)
+22Z = (22Z, 22Z)", Ok(1); "in prefix")]
+    fn test_synchronise(s: &str, result: Result<u64, SyncError>) {
+        assert_eq!(result, sync_str(s));
+    }
+
+    fn cycle(period: u64, cycle_hits: Vec<u64>) -> GhostCycle {
+        GhostCycle { prefix: 0, period, prefix_hits: Vec::new(), cycle_hits }
+    }
+
+    #[test]
+    fn test_many_hits() {
+        // 6^20 combinations of hits, but only 60 distinct steps within the shared period
+        let mut cycles = vec![cycle(6, (0..6).collect()); 20];
+        cycles.push(cycle(10, vec![7]));
+        cycles.push(cycle(4, vec![1, 3]));
+        assert_eq!(Ok(7), synchronise(&cycles));
+    }
+
+    #[test_case(vec![cycle((1 << 61) - 1, vec![1]), cycle((1 << 61) - 3, vec![2])], Err(SyncError::Overflow); "past u64")]
+    #[test_case(vec![cycle((1 << 61) - 1, vec![1]), cycle((1 << 61) - 3, vec![1])], Ok(1); "within u64")]
+    #[test_case(vec![cycle((1 << 61) - 1, vec![1]), cycle((1 << 61) - 3, vec![1]), cycle((1 << 61) - 5, vec![1])], Err(SyncError::Overflow); "past i128")]
+    fn test_synchronise_overflow(cycles: Vec<GhostCycle>, result: Result<u64, SyncError>) {
+        assert_eq!(result, synchronise(&cycles));
+    }
+}
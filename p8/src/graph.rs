@@ -0,0 +1,16 @@
+use crate::{get_lines::get_lines, map::parse_map, network::Network, graph_export::GraphFormat};
+
+pub fn graph_main(args: &Vec<&String>) {
+    if args.len() < 2 {
+        panic!("Must provide the output format (dot or mermaid) and the path of the file to open");
+    }
+    let format = GraphFormat::parse(args[0]).unwrap_or_else(|| panic!("Unknown graph format {}", args[0]));
+    let path = args[1];
+    let mut lines = get_lines(path);
+    // The instructions are not part of the network
+    _ = lines.next();
+    _ = lines.next();
+    let network = Network::from_map(&parse_map(&mut lines));
+
+    print!("{}", network.to_graph().write(format));
+}
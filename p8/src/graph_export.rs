@@ -0,0 +1,132 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeShape {
+    Ellipse, Box, Diamond, Circle
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot, Mermaid
+}
+
+impl GraphFormat {
+    pub fn parse(s: &str) -> Option<GraphFormat> {
+        match s {
+            "dot" => Some(GraphFormat::Dot),
+            "mermaid" => Some(GraphFormat::Mermaid),
+            _ => None
+        }
+    }
+}
+
+struct Edge {
+    from: String,
+    to: String,
+    label: Option<String>
+}
+
+// A directed graph that only exists to be written out for viewing
+#[derive(Default)]
+pub struct Graph {
+    nodes: Vec<(String, NodeShape)>,
+    edges: Vec<Edge>
+}
+
+impl Graph {
+    pub fn new() -> Graph {
+        Graph::default()
+    }
+
+    pub fn add_node(&mut self, name: &str, shape: NodeShape) {
+        self.nodes.push((name.to_string(), shape));
+    }
+
+    pub fn add_edge(&mut self, from: &str, to: &str, label: Option<&str>) {
+        self.edges.push(Edge { from: from.to_string(), to: to.to_string(), label: label.map(|l| l.to_string()) });
+    }
+
+    pub fn write(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::Mermaid => self.to_mermaid(),
+        }
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph {\n");
+        for (name, shape) in self.nodes.iter() {
+            let shape = match shape {
+                NodeShape::Ellipse => "ellipse",
+                NodeShape::Box => "box",
+                NodeShape::Diamond => "diamond",
+                NodeShape::Circle => "doublecircle",
+            };
+            out += &format!("    \"{name}\" [shape={shape}];\n");
+        }
+        for edge in self.edges.iter() {
+            match &edge.label {
+                Some(label) => out += &format!("    \"{}\" -> \"{}\" [label=\"{label}\"];\n", edge.from, edge.to),
+                None => out += &format!("    \"{}\" -> \"{}\";\n", edge.from, edge.to),
+            }
+        }
+        out += "}\n";
+        out
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart LR\n");
+        for (name, shape) in self.nodes.iter() {
+            let node = match shape {
+                NodeShape::Ellipse => format!("{name}([{name}])"),
+                NodeShape::Box => format!("{name}[{name}]"),
+                NodeShape::Diamond => format!("{name}{{{name}}}"),
+                NodeShape::Circle => format!("{name}(({name}))"),
+            };
+            out += &format!("    {node}\n");
+        }
+        for edge in self.edges.iter() {
+            match &edge.label {
+                Some(label) => out += &format!("    {} -->|{label}| {}\n", edge.from, edge.to),
+                None => out += &format!("    {} --> {}\n", edge.from, edge.to),
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Graph, NodeShape};
+
+    #[test]
+    fn vscode_help() { }
+
+    fn small_graph() -> Graph {
+        let mut graph = Graph::new();
+        graph.add_node("a", NodeShape::Box);
+        graph.add_node("b", NodeShape::Diamond);
+        graph.add_edge("a", "b", Some("L"));
+        graph.add_edge("b", "a", None);
+        graph
+    }
+
+    #[test]
+    fn test_dot() {
+        assert_eq!("digraph {
+    \"a\" [shape=box];
+    \"b\" [shape=diamond];
+    \"a\" -> \"b\" [label=\"L\"];
+    \"b\" -> \"a\";
+}
+", small_graph().to_dot());
+    }
+
+    #[test]
+    fn test_mermaid() {
+        assert_eq!("flowchart LR
+    a[a]
+    b{b}
+    a -->|L| b
+    b --> a
+", small_graph().to_mermaid());
+    }
+}
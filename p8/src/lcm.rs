@@ -1,4 +1,4 @@
-pub fn gcd(a: u64, b: u64) -> u64 {
+pub fn gcd(a: i128, b: i128) -> i128 {
     let mut a = a;
     let mut b = b;
     while a != b {
@@ -13,7 +13,8 @@ pub fn gcd(a: u64, b: u64) -> u64 {
     a
 }
 
-pub fn lcm(a: u64, b: u64) -> u64 {
+// None if the lcm does not fit in an i128
+pub fn lcm(a: i128, b: i128) -> Option<i128> {
     let g = gcd(a, b);
-    a * (b / g)
-}
\ No newline at end of file
+    a.checked_mul(b / g)
+}
@@ -24,10 +24,6 @@ impl Direction {
     }
 }
 
-pub trait Navigable {
-    fn go(&self, at: &String, dir: Direction) -> String;
-}
-
 fn parse_line(line: String) -> (String, Destinations) {
     let parts: Vec<_> = line.split(" = ").collect();
     let source = parts[0].trim().to_string();
@@ -41,12 +37,3 @@ pub fn parse_map(lines: &mut impl Iterator<Item=String>) -> HashMap<String, Dest
     map.extend(lines.map(parse_line));
     map
 }
-
-impl Navigable for HashMap<String, Destinations> {
-    fn go(&self, at: &String, dir: Direction) -> String {
-        match dir {
-            Direction::Left => self[at].left.clone(),
-            Direction::Right => self[at].right.clone(),
-        }
-    }
-}
\ No newline at end of file
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+
+use crate::{map::{Destinations, Direction}, graph_export::{Graph, NodeShape}};
+
+// The desert map with interned node names and a dense adjacency list
+pub struct Network {
+    names: Vec<String>,
+    ids: HashMap<String, u32>,
+    edges: Vec<[u32; 2]>
+}
+
+impl Network {
+    pub fn from_map(map: &HashMap<String, Destinations>) -> Network {
+        let mut names: Vec<_> = map.keys().cloned().collect();
+        names.sort();
+        let ids: HashMap<_, _> = names.iter().enumerate()
+            .map(|(idx, name)| (name.clone(), idx as u32))
+            .collect();
+        let edges = names.iter()
+            .map(|name| {
+                let dests = &map[name];
+                [ids[&dests.left], ids[&dests.right]]
+            })
+            .collect();
+        Network { names, ids, edges }
+    }
+
+    pub fn node_count(&self) -> usize { self.edges.len() }
+
+    pub fn id(&self, name: &str) -> Option<u32> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: u32) -> &str {
+        &self.names[id as usize]
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item=u32> {
+        0..self.edges.len() as u32
+    }
+
+    pub fn go(&self, at: u32, dir: Direction) -> u32 {
+        match dir {
+            Direction::Left => self.edges[at as usize][0],
+            Direction::Right => self.edges[at as usize][1],
+        }
+    }
+
+    pub fn to_graph(&self) -> Graph {
+        let mut graph = Graph::new();
+        for (name, [left, right]) in self.names.iter().zip(self.edges.iter()) {
+            // Nodes that go the same way whatever the instruction are worth spotting too
+            let shape = if name.ends_with('A') {
+                NodeShape::Circle
+            }
+            else if name.ends_with('Z') {
+                NodeShape::Box
+            }
+            else if left == right {
+                NodeShape::Diamond
+            }
+            else {
+                NodeShape::Ellipse
+            };
+            graph.add_node(name, shape);
+            if left == right {
+                graph.add_edge(name, self.name(*left), Some("L/R"));
+            }
+            else {
+                graph.add_edge(name, self.name(*left), Some("L"));
+                graph.add_edge(name, self.name(*right), Some("R"));
+            }
+        }
+        graph
+    }
+}
+
+// Where every node lands after one full pass over the instructions,
+// and the first step within that pass that is on an end node
+pub struct JumpTable {
+    cycle_len: usize,
+    lands: Vec<u32>,
+    first_end: Vec<Option<u32>>
+}
+
+impl JumpTable {
+    pub fn new(network: &Network, directions: &[Direction], is_end: impl Fn(u32) -> bool) -> JumpTable {
+        let (lands, first_end) = network.nodes()
+            .map(|start| {
+                let mut at = start;
+                let mut first_end = None;
+                for (step, dir) in directions.iter().enumerate() {
+                    if first_end.is_none() && is_end(at) {
+                        first_end = Some(step as u32);
+                    }
+                    at = network.go(at, *dir);
+                }
+                (at, first_end)
+            })
+            .unzip();
+        JumpTable { cycle_len: directions.len(), lands, first_end }
+    }
+
+    pub fn land(&self, at: u32) -> u32 {
+        self.lands[at as usize]
+    }
+
+    pub fn first_end(&self, at: u32) -> Option<u32> {
+        self.first_end[at as usize]
+    }
+
+    // Number of steps from `start` until the first end node, advancing whole cycles at a time
+    pub fn steps_to_end(&self, start: u32) -> Option<u64> {
+        let mut at = start;
+        // Every cycle boundary is a node, so more cycles than nodes means we are looping forever
+        for cycle in 0..=self.lands.len() as u64 {
+            if let Some(offset) = self.first_end(at) {
+                return Some(cycle * self.cycle_len as u64 + offset as u64);
+            }
+            at = self.land(at);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::map::{Direction, parse_map};
+    use super::{Network, JumpTable};
+
+    #[test]
+    fn vscode_help() { }
+
+    #[test_case("RL
+
+AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)", Some(2); "example 1")]
+    #[test_case("LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)", Some(6); "example 2")]
+    #[test_case("L
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)", None; "never")]
+    fn test_steps_to_end(s: &str, result: Option<u64>) {
+        let mut lines = s.lines().map(|l| l.to_string());
+        let directions = Direction::from_str(lines.next().unwrap().as_str());
+        _ = lines.next();
+        let network = Network::from_map(&parse_map(&mut lines));
+        let end = network.id("ZZZ").unwrap();
+        let jumps = JumpTable::new(&network, &directions, |at| at == end);
+        assert_eq!(result, jumps.steps_to_end(network.id("AAA").unwrap()));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{get_lines::get_lines, map::{Direction, parse_map, Navigable}};
+use crate::{get_lines::get_lines, map::{Direction, parse_map}, network::{Network, JumpTable}};
 
 pub fn part1_main(args: &Vec<&String>) {
     if args.len() < 1 {
@@ -8,17 +8,12 @@ pub fn part1_main(args: &Vec<&String>) {
     let mut lines = get_lines(path);
     let directions = Direction::from_str(lines.next().unwrap().as_str());
     _ = lines.next();
-    let map = parse_map(&mut lines);
+    let network = Network::from_map(&parse_map(&mut lines));
 
-    let mut result = 0u32;
-    let mut index: usize = 0;
-    let mut at = "AAA".to_string();
-    while at != "ZZZ" {
-        let direction = directions[index];
-        index = (index + 1) % directions.len();
-        result += 1;
-        at = map.go(&at, direction)
-    }
+    let start = network.id("AAA").unwrap();
+    let end = network.id("ZZZ").unwrap();
+    let jumps = JumpTable::new(&network, &directions, |at| at == end);
+    let result = jumps.steps_to_end(start).unwrap_or_else(|| panic!("AAA never reaches ZZZ"));
 
     println!("{result}");
-}
\ No newline at end of file
+}
@@ -0,0 +1,34 @@
+use crate::{get_lines::get_lines, row::{Tableau, Row}, poly::Polynomial};
+
+pub fn at_main(args: &Vec<&String>) {
+    if args.len() < 2 {
+        panic!("Must provide the index to evaluate at and the path of the file to open");
+    }
+    let n: i128 = args[0].parse().unwrap();
+    let path = args[1];
+    let (result, degree) = get_lines(path).enumerate()
+        .map(|(idx, line)| {
+            let row = Row::from_line(line);
+            let values: Vec<i128> = row.values().map(|v| v as i128).collect();
+            let poly = Polynomial::from_sequence(&values);
+            match &poly {
+                Some(poly) if !poly.is_low_degree() =>
+                    eprintln!("warning: line {idx} only fits a polynomial of degree {}, extrapolating it is a guess", poly.degree()),
+                Some(_) => (),
+                None => eprintln!("warning: line {idx} overflows fitting a polynomial, so it is not cross-checked"),
+            }
+            let tableau = Tableau::new(row);
+            let value = tableau.value_at(n).unwrap_or_else(|| panic!("Overflow evaluating line {idx} at {n}"));
+            // The fit is independent of the tableau, so use it as a cross-check where it does not overflow
+            if let Some(check) = poly.and_then(|poly| poly.eval(n)) {
+                assert_eq!(Some(value), check.to_integer(), "Tableau and polynomial fit disagree on line {idx}");
+            }
+            (value, tableau.degree())
+        })
+        .fold((0i128, 0usize), |(total, degree), (value, d)| {
+            (total.checked_add(value).unwrap_or_else(|| panic!("Overflow summing at {n}")), degree.max(d))
+        });
+
+    println!("max degree {degree}");
+    println!("{result}");
+}
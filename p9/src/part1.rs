@@ -5,7 +5,7 @@ pub fn part1_main(args: &Vec<&String>) {
         panic!("Must provide the path of the file to open");
     }
     let path = args[0];
-    let result: i32 = get_lines(path)
+    let result: i64 = get_lines(path)
         .map(|line| Tableau::new(Row::from_line(line)))
         .map(|mut t| t.extend())
         .sum();
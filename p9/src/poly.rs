@@ -0,0 +1,207 @@
+use std::{ops::{Add, Sub, Mul, Div, Neg}, fmt::Display};
+
+// None when the answer is 2^127, which only happens with i128::MIN
+pub fn gcd(a: i128, b: i128) -> Option<i128> {
+    if b == 0 { a.checked_abs() } else { gcd(b, a.checked_rem(b)?) }
+}
+
+// An exact fraction, always stored in lowest terms with a positive denominator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128
+}
+
+impl Rational {
+    // None for a zero denominator, or when lowest terms do not fit, such as 1 / i128::MIN
+    pub fn checked_new(num: i128, den: i128) -> Option<Rational> {
+        if den == 0 {
+            return None;
+        }
+        let g = gcd(num, den)?.max(1).checked_mul(den.signum())?;
+        Some(Rational { num: num.checked_div(g)?, den: den.checked_div(g)? })
+    }
+
+    pub fn zero() -> Rational { Rational { num: 0, den: 1 } }
+
+    pub fn is_zero(&self) -> bool { self.num == 0 }
+
+    pub fn to_integer(self) -> Option<i128> {
+        if self.den == 1 { Some(self.num) } else { None }
+    }
+
+    pub fn checked_add(self, rhs: Rational) -> Option<Rational> {
+        let num = self.num.checked_mul(rhs.den)?.checked_add(rhs.num.checked_mul(self.den)?)?;
+        Rational::checked_new(num, self.den.checked_mul(rhs.den)?)
+    }
+
+    pub fn checked_neg(self) -> Option<Rational> {
+        Some(Rational { num: self.num.checked_neg()?, den: self.den })
+    }
+
+    pub fn checked_sub(self, rhs: Rational) -> Option<Rational> {
+        self.checked_add(rhs.checked_neg()?)
+    }
+
+    pub fn checked_mul(self, rhs: Rational) -> Option<Rational> {
+        Rational::checked_new(self.num.checked_mul(rhs.num)?, self.den.checked_mul(rhs.den)?)
+    }
+
+    pub fn checked_div(self, rhs: Rational) -> Option<Rational> {
+        if rhs.is_zero() {
+            return None;
+        }
+        Rational::checked_new(self.num.checked_mul(rhs.den)?, self.den.checked_mul(rhs.num)?)
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(value: i128) -> Self {
+        Rational { num: value, den: 1 }
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        }
+        else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+    fn add(self, rhs: Rational) -> Rational { self.checked_add(rhs).expect("Rational overflow") }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+    fn sub(self, rhs: Rational) -> Rational { self.checked_sub(rhs).expect("Rational overflow") }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+    fn neg(self) -> Rational { self.checked_neg().expect("Rational overflow") }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+    fn mul(self, rhs: Rational) -> Rational { self.checked_mul(rhs).expect("Rational overflow") }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+    fn div(self, rhs: Rational) -> Rational { self.checked_div(rhs).expect("Rational overflow or division by zero") }
+}
+
+// The interpolating polynomial of some samples, in Newton form
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    xs: Vec<Rational>,
+    coeffs: Vec<Rational>
+}
+
+impl Polynomial {
+    // Newton's divided differences over samples with distinct x, returning None on overflow
+    pub fn interpolate(points: &[(i128, i128)]) -> Option<Polynomial> {
+        let xs: Vec<Rational> = points.iter().map(|(x, _)| (*x).into()).collect();
+        let mut diffs: Vec<Rational> = points.iter().map(|(_, y)| (*y).into()).collect();
+        let mut coeffs = Vec::with_capacity(points.len());
+        for order in 0..points.len() {
+            coeffs.push(diffs[0]);
+            diffs = (0..diffs.len() - 1)
+                .map(|i| diffs[i + 1].checked_sub(diffs[i])?.checked_div(xs[i + order + 1].checked_sub(xs[i])?))
+                .collect::<Option<_>>()?;
+        }
+        Some(Polynomial { xs, coeffs })
+    }
+
+    // Interpolates a sequence sampled at 0, 1, 2, ...
+    pub fn from_sequence(values: &[i128]) -> Option<Polynomial> {
+        let points: Vec<_> = values.iter().enumerate().map(|(x, y)| (x as i128, *y)).collect();
+        Polynomial::interpolate(&points)
+    }
+
+    pub fn sample_count(&self) -> usize { self.xs.len() }
+
+    pub fn degree(&self) -> usize {
+        self.coeffs.iter().rposition(|c| !c.is_zero()).unwrap_or(0)
+    }
+
+    // With n samples any sequence fits a polynomial of degree n - 1, so only a lower degree says anything
+    pub fn is_low_degree(&self) -> bool {
+        self.sample_count() == 0 || self.degree() + 1 < self.sample_count()
+    }
+
+    // Horner's scheme over the Newton basis, returning None on overflow
+    pub fn eval(&self, x: i128) -> Option<Rational> {
+        let x: Rational = x.into();
+        self.coeffs.iter().enumerate().rev()
+            .try_fold(Rational::zero(), |acc, (i, c)| acc.checked_mul(x.checked_sub(self.xs[i])?)?.checked_add(*c))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::{Polynomial, Rational};
+
+    #[test]
+    fn vscode_help() { }
+
+    fn rational(num: i128, den: i128) -> Rational {
+        Rational::checked_new(num, den).unwrap()
+    }
+
+    #[test_case(6, 4, 3, 2; "reduce")]
+    #[test_case(-6, 4, -3, 2; "negative numerator")]
+    #[test_case(6, -4, -3, 2; "negative denominator")]
+    #[test_case(0, -4, 0, 1; "zero")]
+    fn test_rational_new(num: i128, den: i128, rnum: i128, rden: i128) {
+        let r = rational(num, den);
+        assert_eq!((rnum, rden), (r.num, r.den));
+    }
+
+    #[test_case(&[], 0, true; "empty")]
+    #[test_case(&[3, 3, 3], 0, true; "constant")]
+    #[test_case(&[0, 3, 6, 9, 12, 15], 1, true; "linear")]
+    #[test_case(&[10, 13, 16, 21, 30, 45], 3, true; "cubic")]
+    #[test_case(&[1, 2, 4, 8, 16], 4, false; "exponential")]
+    fn test_degree(values: &[i128], degree: usize, low: bool) {
+        let poly = Polynomial::from_sequence(values).unwrap();
+        assert_eq!(degree, poly.degree());
+        assert_eq!(low, poly.is_low_degree());
+    }
+
+    #[test_case(&[(0, 0), (1, 1), (2, 4)], 10, rational(100, 1); "square")]
+    #[test_case(&[(5, 1), (-5, 1), (15, 1)], 1000, rational(1, 1); "constant")]
+    #[test_case(&[(0, 0), (2, 1)], 1, rational(1, 2); "fraction")]
+    #[test_case(&[(0, 1), (1, 2), (2, 3)], i128::MAX - 1, Rational::from(i128::MAX); "large")]
+    fn test_eval(points: &[(i128, i128)], x: i128, y: Rational) {
+        assert_eq!(Some(y), Polynomial::interpolate(points).unwrap().eval(x));
+    }
+
+    #[test]
+    fn test_eval_overflow() {
+        assert_eq!(None, Polynomial::from_sequence(&[0, 1, 4]).unwrap().eval(i128::MAX));
+    }
+
+    #[test_case(&[(0, i128::MIN), (1, i128::MAX)]; "difference")]
+    #[test_case(&[(i128::MIN, 0), (i128::MAX, 1)]; "spacing")]
+    #[test_case(&[(0, 0), (1, i128::MIN), (2, 0)]; "second difference")]
+    fn test_interpolate_overflow(points: &[(i128, i128)]) {
+        assert_eq!(None, Polynomial::interpolate(points));
+    }
+
+    #[test]
+    fn test_rational_limits() {
+        assert_eq!(None, Rational::from(i128::MIN).checked_neg());
+        assert_eq!(None, Rational::checked_new(1, i128::MIN));
+        assert_eq!(Some(Rational::from(i128::MIN)), Rational::checked_new(i128::MIN, 1));
+        assert_eq!(None, Rational::from(1).checked_div(Rational::from(i128::MIN)));
+    }
+}
@@ -1,7 +1,9 @@
 use std::collections::VecDeque;
 
+use crate::poly::gcd;
+
 pub struct Row {
-    data: VecDeque<i32>
+    data: VecDeque<i64>
 }
 
 impl Row {
@@ -30,21 +32,31 @@ impl Row {
         self.data.iter().all(|x| *x == 0)
     }
 
-    pub fn extend(&mut self, diff: i32) -> i32 {
+    pub fn extend(&mut self, diff: i64) -> i64 {
         let val = self.data.back().map(|x| *x).unwrap_or(0) + diff;
         self.data.push_back(val);
         val
     }
 
-    pub fn prepend(&mut self, diff: i32) -> i32 {
+    pub fn prepend(&mut self, diff: i64) -> i64 {
         let val = self.data.front().map(|x| *x).unwrap_or(0) - diff;
         self.data.push_front(val);
         val
     }
+
+    pub fn values(&self) -> impl Iterator<Item=i64> + '_ {
+        self.data.iter().copied()
+    }
+
+    pub fn first(&self) -> i64 {
+        self.data.front().copied().unwrap_or(0)
+    }
 }
 
 pub struct Tableau {
-    rows: Vec<Row>
+    rows: Vec<Row>,
+    // How many values have been prepended, so that index 0 stays the first value of the input
+    offset: i128
 }
 
 impl Tableau {
@@ -53,15 +65,41 @@ impl Tableau {
         while !rows.last().unwrap().is_all_same() {
             rows.push(rows.last().unwrap().difference_row());
         }
-        Tableau { rows }
+        Tableau { rows, offset: 0 }
+    }
+
+    // Degree of the polynomial the tableau describes
+    pub fn degree(&self) -> usize {
+        self.rows.len() - 1
+    }
+
+    // Newton's forward difference formula: f(k) = sum_j C(k, j) * first difference of order j.
+    // Works for any index, including negative ones, and returns None on overflow.
+    pub fn value_at(&self, n: i128) -> Option<i128> {
+        let k = n.checked_add(self.offset)?;
+        let mut binomial = 1i128;
+        let mut total = 0i128;
+        for (j, row) in self.rows.iter().enumerate() {
+            if j > 0 {
+                // C(k, j) = C(k, j - 1) * (k - j + 1) / j. The division is exact, so whatever part of j
+                // does not divide C(k, j - 1) divides k - j + 1, and dividing first only overflows
+                // when C(k, j) itself does not fit
+                let j = j as i128;
+                let g = gcd(binomial, j)?;
+                binomial = (binomial / g).checked_mul(k.checked_sub(j - 1)? / (j / g))?;
+            }
+            total = total.checked_add(binomial.checked_mul(row.first() as i128)?)?;
+        }
+        Some(total)
     }
 
-    pub fn extend(&mut self) -> i32 {
+    pub fn extend(&mut self) -> i64 {
         self.rows.iter_mut().rev()
             .fold(0, |diff, row| row.extend(diff))
     }
 
-    pub fn prepend(&mut self) -> i32 {
+    pub fn prepend(&mut self) -> i64 {
+        self.offset += 1;
         self.rows.iter_mut().rev()
             .fold(0, |diff, row| row.prepend(diff))
     }
@@ -84,7 +122,7 @@ mod test {
     #[test_case("1 2 3 4 5", 6, 7, 8; "linear")]
     #[test_case("2 5 10 17 26", 37, 50, 65; "n=2")]
     #[test_case("-10 -5 2 14 34", 65, 110, 172; "max")]
-    fn test_extend(line: &str, v1: i32, v2: i32, v3: i32) {
+    fn test_extend(line: &str, v1: i64, v2: i64, v3: i64) {
         let mut tableau = Tableau::new(Row::from_line(line.into()));
         assert_eq!(v1, tableau.extend());
         assert_eq!(v2, tableau.extend());
@@ -97,10 +135,37 @@ mod test {
     #[test_case(1, 2, 3, "4 5 6 7 8"; "linear")]
     #[test_case(2, 5, 10, "17 26 37 50 65"; "n=2")]
     #[test_case(-10, -5, 2, "14 34 65 110 172"; "max")]
-    fn test_prepend(v3: i32, v2: i32, v1: i32, line: &str) {
+    fn test_prepend(v3: i64, v2: i64, v1: i64, line: &str) {
         let mut tableau = Tableau::new(Row::from_line(line.into()));
         assert_eq!(v1, tableau.prepend());
         assert_eq!(v2, tableau.prepend());
         assert_eq!(v3, tableau.prepend());
     }
-}
\ No newline at end of file
+
+    #[test_case("", 0, Some(0), 0; "empty")]
+    #[test_case("1 2 3 4 5", 999, Some(1000), 1; "linear")]
+    #[test_case("2 5 10 17 26", -3, Some(5), 2; "negative")]
+    #[test_case("-10 -5 2 14 34", 7, Some(172), 3; "cubic")]
+    #[test_case("0 1 8 27 64", 1_000_000, Some(1_000_000_000_000_000_000), 3; "far")]
+    #[test_case("0 1 4 9 16", i128::MAX, None, 2; "overflow")]
+    #[test_case("0 1 4 9 16", i128::MIN, None, 2; "overflow below")]
+    #[test_case("5 5 5", i128::MIN, Some(5), 0; "constant at min")]
+    #[test_case("1 2 3", i128::MIN, Some(i128::MIN + 1), 1; "linear at min")]
+    #[test_case("1 2 3", i128::MAX - 1, Some(i128::MAX), 1; "linear at max")]
+    #[test_case("0 0 1 3 6", 1 << 64, Some(i128::MAX - i64::MAX as i128), 2; "binomial near max")]
+    fn test_value_at(line: &str, n: i128, value: Option<i128>, degree: usize) {
+        let tableau = Tableau::new(Row::from_line(line.into()));
+        assert_eq!(value, tableau.value_at(n));
+        assert_eq!(degree, tableau.degree());
+    }
+
+    #[test_case("10 13 16 21 30 45")]
+    #[test_case("-10 -5 2 14 34")]
+    fn test_value_at_after_prepend(line: &str) {
+        let mut tableau = Tableau::new(Row::from_line(line.into()));
+        let before = tableau.prepend();
+        let after = tableau.extend();
+        assert_eq!(Some(before as i128), tableau.value_at(-1));
+        assert_eq!(Some(after as i128), tableau.value_at(line.split_ascii_whitespace().count() as i128));
+    }
+}